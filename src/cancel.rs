@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Token to cancel long-running DMM operations from other thread.
+///
+/// Only operations documented as cancellable check the token, and only when
+/// no reply is left pending (between requests to DMM or while waiting for measurements),
+/// then they return `ErrorKind::Cancelled`.
+/// The token is reset when a cancellable operation returns,
+/// so cancellation requested while no operation is running cancels the next one.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Requests cancellation of current operation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Is cancellation requested?
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Clears requested cancellation.
+    pub(crate) fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    /// Runs cancellable operation `f` and resets the token after it returns.
+    pub(crate) fn run<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        let result = f();
+        self.reset();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancellationToken;
+
+    #[test]
+    fn test_cancel() {
+        let token = CancellationToken::default();
+        let other = token.clone();
+        assert!(!token.is_cancelled());
+        other.cancel();
+        assert!(token.is_cancelled());
+        token.reset();
+        assert!(!other.is_cancelled());
+    }
+
+    #[test]
+    fn test_run() {
+        let token = CancellationToken::default();
+        // cancellation requested before the operation isn't lost
+        token.cancel();
+        assert!(token.run(|| token.is_cancelled()));
+        assert!(!token.is_cancelled());
+        assert!(!token.run(|| token.is_cancelled()));
+        // and requested during the operation doesn't affect the next one
+        token.run(|| token.cancel());
+        assert!(!token.is_cancelled());
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use error::CommandContext;
+use packet::Packet;
+
+/// A maximum number of unexpected messages kept in error context.
+const UNEXPECTED_LENGTH: usize = 5;
+
+/// Command sent to DMM.
+pub(crate) struct Command {
+    name: &'static str,
+    body: Vec<u8>,
+    sent: Instant,
+    is_sent: bool,
+}
+
+impl Command {
+    pub(crate) fn new(name: &'static str, body: &[u8]) -> Command {
+        Command {
+            name,
+            body: body.to_vec(),
+            sent: Instant::now(),
+            is_sent: false,
+        }
+    }
+
+    pub(crate) fn packet(&self) -> Packet {
+        Packet::new(&self.body)
+    }
+
+    /// Marks the command as sent right now.
+    pub(crate) fn mark_sent(&mut self) {
+        self.sent = Instant::now();
+        self.is_sent = true;
+    }
+
+    /// Has the command been sent (so a reply is pending)?
+    pub(crate) fn is_sent(&self) -> bool {
+        self.is_sent
+    }
+
+    /// Time elapsed since the command has been sent.
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.sent.elapsed()
+    }
+
+    /// Can the command be repeated without side effects?
+    pub(crate) fn is_idempotent(&self) -> bool {
+        match self.body.first() {
+            // toggle HOLD
+            Some(&0x12) => false,
+            // min/max mode on (it resets min/max values)
+            Some(&0x04) => self.body.get(1) == Some(&0x00),
+            // save measurement, delete saved measurement(s), start recording
+            Some(&0x06) | Some(&0x09) | Some(&0x0A) => false,
+            _ => true,
+        }
+    }
+
+    pub(crate) fn context(&self, unexpected: &Unexpected) -> CommandContext {
+        CommandContext {
+            name: self.name,
+            args: if self.body.is_empty() {
+                Vec::new()
+            } else {
+                self.body[1..].to_vec()
+            },
+            elapsed: self.elapsed(),
+            unexpected: unexpected.0.iter().cloned().collect(),
+            idempotent: self.is_idempotent(),
+        }
+    }
+}
+
+/// Last messages received while waiting a reply.
+#[derive(Default)]
+pub(crate) struct Unexpected(VecDeque<String>);
+
+impl Unexpected {
+    pub(crate) fn push(&mut self, description: String) {
+        if self.0.len() == UNEXPECTED_LENGTH {
+            self.0.pop_front();
+        }
+        self.0.push_back(description);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, Unexpected};
+
+    #[test]
+    fn test_idempotent() {
+        assert!(Command::new("SET_MODE", &[0x01, 0x11, 0x31]).is_idempotent());
+        assert!(Command::new("SET_MIN_MAX_MODE", &[0x04, 0x00]).is_idempotent());
+        assert!(!Command::new("SET_MIN_MAX_MODE", &[0x04, 0x01]).is_idempotent());
+        assert!(!Command::new("TOGGLE_HOLD", &[0x12, 0x5A]).is_idempotent());
+        assert!(Command::new("GET_MEASUREMENT", &[]).is_idempotent());
+    }
+
+    #[test]
+    fn test_context() {
+        let cmd = Command::new("SET_RANGE", &[0x02, 0x03]);
+        let mut unexpected = Unexpected::default();
+        for i in 0..7 {
+            unexpected.push(format!("msg{}", i));
+        }
+        let ctx = cmd.context(&unexpected);
+        assert_eq!(ctx.name, "SET_RANGE");
+        assert_eq!(ctx.args, vec![0x03]);
+        assert_eq!(ctx.unexpected, vec!["msg2", "msg3", "msg4", "msg5", "msg6"]);
+        assert!(ctx.idempotent);
+    }
+}
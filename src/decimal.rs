@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use error::*;
+
+/// Maximum number of digits after decimal point (`10^MAX_SCALE` fits `i64`).
+pub const MAX_SCALE: u32 = 18;
+
+/// Exact decimal number: `mantissa * 10^-scale`.
+///
+/// It keeps the same digits as DMM display (1.0003 V is `Decimal { mantissa: 10003, scale: 4 }`).
+/// Numerically equal decimals with different scales (1.5 and 1.50) are equal.
+///
+/// With `serde` feature it's (de)serialized as a string ("1.0003").
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "String", try_from = "String"))]
+pub struct Decimal {
+    /// Integer mantissa.
+    pub mantissa: i64,
+
+    /// A number of digits after decimal point.
+    pub scale: u32,
+}
+
+impl Decimal {
+    /// Rounds `value` to `scale` digits after decimal point.
+    ///
+    /// Returns `None` if `value` isn't finite, `scale` exceeds `MAX_SCALE`
+    /// or the mantissa doesn't fit `i64`.
+    pub fn from_f32(value: f32, scale: u32) -> Option<Decimal> {
+        if scale > MAX_SCALE {
+            return None;
+        }
+        let mantissa = (f64::from(value) * 10f64.powi(scale as i32)).round();
+        // i64::MAX as f64 is rounded up to 2^63
+        if !mantissa.is_finite() || mantissa < i64::MIN as f64 || mantissa >= i64::MAX as f64 {
+            return None;
+        }
+        Some(Decimal {
+            mantissa: mantissa as i64,
+            scale,
+        })
+    }
+
+    /// Converts to `f64` (it can be inexact).
+    pub fn to_f64(self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Returns mantissa scaled to `scale` (`scale` should be greater or equal to `self.scale`).
+    ///
+    /// Returns `None` on overflow.
+    fn rescale(self, scale: u32) -> Option<i128> {
+        if self.mantissa == 0 {
+            return Some(0);
+        }
+        10i128
+            .checked_pow(scale - self.scale)
+            .and_then(|factor| i128::from(self.mantissa).checked_mul(factor))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        // rescaled mantissa overflows only if its magnitude is greater than the other one
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (None, _) => self.mantissa.cmp(&0),
+            (_, None) => 0.cmp(&other.mantissa),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, int, frac)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    /// Parses decimal number ("-1.0003", "42", "+0.50").
+    fn from_str(s: &str) -> Result<Decimal> {
+        let invalid = || Error::from(ErrorKind::InvalidDecimal(s.into()));
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(&b'-') => (true, &s[1..]),
+            Some(&b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int, frac) = match unsigned.find('.') {
+            Some(i) => (&unsigned[..i], &unsigned[(i + 1)..]),
+            None => (unsigned, ""),
+        };
+        if int.is_empty() && frac.is_empty()
+            || frac.len() > MAX_SCALE as usize
+            || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let mantissa: i64 = format!("{}{}", int, frac).parse().map_err(|_| invalid())?;
+        Ok(Decimal {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: frac.len() as u32,
+        })
+    }
+}
+
+impl From<Decimal> for String {
+    fn from(decimal: Decimal) -> String {
+        decimal.to_string()
+    }
+}
+
+impl TryFrom<String> for Decimal {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Decimal> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decimal;
+
+    #[test]
+    fn test_from_f32() {
+        let d = Decimal::from_f32(1.0003, 4).unwrap();
+        assert_eq!(d.mantissa, 10003);
+        assert_eq!(d.to_string(), "1.0003");
+        assert_eq!(Decimal::from_f32(-0.05, 3).unwrap().to_string(), "-0.050");
+        assert_eq!(Decimal::from_f32(600.0, 0).unwrap().to_string(), "600");
+        assert!(Decimal::from_f32(1.0, 19).is_none());
+        assert!(Decimal::from_f32(1e10, 18).is_none());
+        assert!(Decimal::from_f32(f32::INFINITY, 0).is_none());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let d: Decimal = "-1.0003".parse().unwrap();
+        assert_eq!(d.mantissa, -10003);
+        assert_eq!(d.scale, 4);
+        assert_eq!(d.to_string(), "-1.0003");
+        assert_eq!("+.5".parse::<Decimal>().unwrap().to_string(), "0.5");
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("-".parse::<Decimal>().is_err());
+        assert!("0.0000000000000000001".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_ord() {
+        let a: Decimal = "1.5".parse().unwrap();
+        let b: Decimal = "1.50".parse().unwrap();
+        let c: Decimal = "1.0003".parse().unwrap();
+        assert_eq!(a, b);
+        assert!(c < a);
+        assert!("-2".parse::<Decimal>().unwrap() < c);
+
+        let max = Decimal {
+            mantissa: i64::MAX,
+            scale: 0,
+        };
+        let small = Decimal {
+            mantissa: -1,
+            scale: 1000,
+        };
+        let min = Decimal {
+            mantissa: i64::MIN,
+            scale: 0,
+        };
+        assert!(min < small && small < max);
+        assert_eq!(
+            Decimal {
+                mantissa: 0,
+                scale: 1000
+            },
+            Decimal {
+                mantissa: 0,
+                scale: 0
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        let d: Decimal = "-1.0300".parse().unwrap();
+        let json = ::serde_json::to_value(d).unwrap();
+        assert_eq!(json, "-1.0300");
+        let d: Decimal = ::serde_json::from_value(json).unwrap();
+        assert_eq!((d.mantissa, d.scale), (-10300, 4));
+        assert!(::serde_json::from_str::<Decimal>("\"1e5\"").is_err());
+    }
+}
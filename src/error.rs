@@ -1,24 +1,128 @@
+// `error_chain!` 0.11 expands to deprecated `Error::description`/`Error::cause` calls.
+#![allow(deprecated)]
+
+use std::fmt;
+use std::time::Duration;
+
+use mode::Mode;
+use range::Range;
+use unit::{Unit, UnitExp};
+
+/// Details of a command sent to DMM.
+///
+/// It's attached to errors occured while the command is executed.
+#[derive(Clone, Debug)]
+pub struct CommandContext {
+    /// Command name (`"SET_MODE"`, `"GET_RECORD_INFO"`, etc).
+    pub name: &'static str,
+
+    /// Command arguments (payload without command code).
+    pub args: Vec<u8>,
+
+    /// Time elapsed since the command has been sent.
+    pub elapsed: Duration,
+
+    /// Last messages received from DMM while waiting a reply (oldest first).
+    pub unexpected: Vec<String>,
+
+    /// Is it safe to send the command again?
+    ///
+    /// It's `false` for commands changing DMM state relative to previous state
+    /// (toggle HOLD, save measurement, start recording, etc).
+    pub idempotent: bool,
+}
+
+impl fmt::Display for CommandContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}'", self.name)?;
+        if !self.args.is_empty() {
+            f.write_str(" [")?;
+            for (i, b) in self.args.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" ")?;
+                }
+                write!(f, "{:02X}", b)?;
+            }
+            f.write_str("]")?;
+        }
+        write!(f, " after {} ms", self.elapsed.as_millis())?;
+        if !self.unexpected.is_empty() {
+            write!(f, ", unexpected messages: {}", self.unexpected.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 error_chain! {
     foreign_links {
         HidError(::hid::Error);
         UartError(::cp211x_uart::Error);
+        IoError(::std::io::Error);
     }
     errors {
         OutOfRange {
             description("Argument is out of range")
             display("argument is out of range")
         }
-        CommandWrite(cmd: &'static str) {
+        Cancelled {
+            description("Operation is cancelled")
+            display("operation is cancelled")
+        }
+        CommandWrite(ctx: CommandContext) {
             description("Command writing error")
-            display("can't write command '{}' to DMM", cmd)
+            display("can't write command {} to DMM", ctx)
+        }
+        CommandRead(ctx: CommandContext) {
+            description("Reply reading error")
+            display("can't read reply to command {} from DMM", ctx)
         }
-        CommandError {
+        CommandError(ctx: CommandContext) {
             description("Command execution error")
-            display("can't execute command due to error")
+            display("DMM replied with error to command {}", ctx)
         }
-        WaitTimeout {
+        WaitTimeout(ctx: CommandContext) {
             description("Wait timeout is exceed")
-            display("did not receive message from DMM, timeout is exceed")
+            display("did not receive reply to command {} from DMM, timeout is exceed", ctx)
+        }
+        UnexpectedMode(requested: Mode, actual: Mode) {
+            description("DMM switched to unexpected mode")
+            display("DMM switched to mode '{}' instead of '{}'", actual, requested)
+        }
+        UnexpectedRange(mode: Mode, requested: Range, actual: Range) {
+            description("DMM switched to unexpected range")
+            display("DMM switched to range {:?} instead of {:?} in mode '{}'", actual, requested, mode)
+        }
+        NoRelativeMode(mode: Mode) {
+            description("Mode has no relative counterpart")
+            display("mode '{}' has no relative counterpart", mode)
+        }
+        UnknownModeName(name: String) {
+            description("Unknown mode name")
+            display("unknown mode name '{}'", name)
+        }
+        UnknownRangeName(name: String) {
+            description("Unknown range name")
+            display("unknown range name '{}'", name)
+        }
+        UnknownUnitName(name: String) {
+            description("Unknown unit name")
+            display("unknown unit name '{}'", name)
+        }
+        IncompatibleUnits(from: UnitExp, to: UnitExp) {
+            description("Incompatible units")
+            display("incompatible units ({} and {})", from, to)
+        }
+        IncomparableValues {
+            description("Values can't be compared (NaN)")
+            display("values can't be compared (NaN)")
+        }
+        NoQuantity(unit: Unit) {
+            description("Unit has no corresponding physical quantity")
+            display("unit {} has no corresponding physical quantity", unit)
+        }
+        InvalidDecimal(s: String) {
+            description("Invalid decimal number")
+            display("invalid decimal number '{}'", s)
         }
         InvalidDateTime(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) {
             description("Invalid date and time")
@@ -38,7 +142,7 @@ error_chain! {
         }
         RecordDurationIsOutOfRange(duration: u32) {
             description("Record duration is out of range (1..143999 minute(s))")
-            display("record interval is out of range (1..143999 minute(s)): {}", duration)
+            display("record duration is out of range (1..143999 minute(s)): {}", duration)
         }
         UnknownReplyCode(code: u16) {
             description("Unknown reply code")
@@ -66,3 +170,48 @@ error_chain! {
         }
     }
 }
+
+impl ErrorKind {
+    /// Returns the command which caused the error.
+    pub fn command(&self) -> Option<&CommandContext> {
+        match *self {
+            ErrorKind::CommandWrite(ref ctx)
+            | ErrorKind::CommandRead(ref ctx)
+            | ErrorKind::CommandError(ref ctx)
+            | ErrorKind::WaitTimeout(ref ctx) => Some(ctx),
+            _ => None,
+        }
+    }
+
+    /// Is it safe to retry the failed operation?
+    ///
+    /// DMM doesn't change its state if it replies with error,
+    /// so the command can be repeated.
+    /// If the command was not delivered or the reply was lost (or unreadable)
+    /// it's safe to repeat idempotent commands only.
+    pub fn is_retry_safe(&self) -> bool {
+        match *self {
+            ErrorKind::CommandError(_) => true,
+            ErrorKind::CommandWrite(ref ctx)
+            | ErrorKind::CommandRead(ref ctx)
+            | ErrorKind::WaitTimeout(ref ctx) => ctx.idempotent,
+            _ => false,
+        }
+    }
+}
+
+impl Error {
+    /// Returns the command which caused the error.
+    ///
+    /// See `ErrorKind::command`.
+    pub fn command(&self) -> Option<&CommandContext> {
+        self.kind().command()
+    }
+
+    /// Is it safe to retry the failed operation?
+    ///
+    /// See `ErrorKind::is_retry_safe`.
+    pub fn is_retry_safe(&self) -> bool {
+        self.kind().is_retry_safe()
+    }
+}
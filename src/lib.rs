@@ -1,4 +1,4 @@
-#![recursion_limit = "128"]
+#![recursion_limit = "256"]
 
 extern crate byteorder;
 extern crate chrono;
@@ -8,51 +8,115 @@ extern crate error_chain;
 extern crate hid;
 #[macro_use]
 extern crate nom;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(feature = "uom")]
+extern crate uom;
 
 mod error;
 use error::*;
-pub use error::{Error, ErrorKind};
+pub use error::{CommandContext, Error, ErrorKind};
+pub use cancel::CancellationToken;
 
 mod packet;
+mod cancel;
+mod command;
 mod range;
 mod mode;
 mod unit;
+mod decimal;
 mod value;
+#[cfg(feature = "uom")]
+mod quantity;
 mod message;
+mod logger;
 mod measurement;
+mod min_max;
 mod rec_info;
+mod rec_status;
+mod rec_config;
 mod rec_data;
+mod rec_verify;
+mod reading;
+mod screen;
+mod settling;
+mod state;
+mod timestamped;
 mod utils;
 
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 use std::vec::Vec;
 
 use byteorder::{ByteOrder, LittleEndian};
 use chrono::NaiveDateTime;
 
-use packet::Packet;
+use command::{Command, Unexpected};
 use message::Message;
-pub use value::Value;
+use timestamped::Received;
+pub use decimal::Decimal;
+pub use value::{Value, ValueDisplay};
+#[cfg(feature = "uom")]
+pub use quantity::Quantity;
+pub use logger::{Logger, LoggerConfig, Sampling};
 pub use measurement::{Measurement, MinMaxMeasurement, NormalMeasurement, PeakMeasurement,
                       RelativeMeasurement};
-pub use mode::Mode;
+pub use min_max::MinMaxSession;
+pub use mode::{Function, Mode, Submode};
 pub use range::Range;
-pub use unit::{Unit, UnitExp};
+pub use unit::{Unit, UnitExp, UnitExpDisplay, UnitStyle};
 pub use rec_info::RecordInfo;
-pub use rec_data::RecordDataItem;
+pub use rec_status::RecordStatus;
+pub use rec_config::{RecordConfig, RecordName};
+pub use rec_data::{RecordDataItem, RecordProgress};
+pub use rec_verify::{RecordIssue, RecordReport, RecordStatistic};
+pub use reading::{AcWithFrequency, DecibelReading, Reading, TemperatureReading};
+pub use screen::{Screen, ScreenField};
+pub use settling::Settling;
+pub use state::DeviceState;
+pub use timestamped::TimestampedMeasurement;
 
 const RX_BUF_LENGTH: usize = 4096; // it should be 2.5KB at least
 const WAIT_TIMEOUT: u64 = 5000; // 5 seconds
+const MEASUREMENT_PERIOD: u64 = 1000; // upper bound of interval between measurements, 1 second
+const READ_TIMEOUT: u64 = 100; // 100 ms
+const DRAIN_READ_TIMEOUT: u64 = 10; // 10 ms
+
+/// Measurement state relative to requested mode or range.
+enum Switch {
+    /// Measurement confirms requested state.
+    Done,
+
+    /// DMM is still in previous state.
+    Pending,
+
+    /// DMM is in other state.
+    Failed,
+}
 
 pub struct Dmm {
     uart: cp211x_uart::HidUart,
     rx_buf: Vec<u8>,
+    /// Byte count and host time of each UART read whose bytes are still in `rx_buf`.
+    rx_times: VecDeque<(usize, NaiveDateTime, Instant)>,
+    state: DeviceState,
+    min_max: min_max::MinMaxTracker,
+    /// Sequence number of the next measurement.
+    sequence: u64,
+    cancel: CancellationToken,
+    /// Is a cancellable operation running?
+    is_cancellable: bool,
+    /// Config of the last record started by `start_recording`.
+    record_config: Option<RecordConfig>,
 }
 
 impl Dmm {
     pub fn new(handle: hid::Handle) -> Result<Dmm> {
         let mut uart = cp211x_uart::HidUart::new(handle)?;
-        uart.set_read_timeout(Duration::from_millis(100));
+        uart.set_read_timeout(Duration::from_millis(READ_TIMEOUT));
         uart.set_write_timeout(Duration::from_millis(500));
         uart.set_config(&cp211x_uart::UartConfig {
             baud_rate: 9600,
@@ -65,51 +129,75 @@ impl Dmm {
         Ok(Dmm {
             uart,
             rx_buf: Vec::with_capacity(RX_BUF_LENGTH),
+            rx_times: VecDeque::new(),
+            state: DeviceState::default(),
+            min_max: min_max::MinMaxTracker::default(),
+            sequence: 0,
+            cancel: CancellationToken::default(),
+            is_cancellable: false,
+            record_config: None,
         })
     }
 
+    /// Returns token to cancel running operation from other thread.
+    ///
+    /// See `CancellationToken`.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Returns last known DMM state.
+    pub fn state(&self) -> &DeviceState {
+        &self.state
+    }
+
     /// Emulates 'Hold' button.
     pub fn toggle_hold(&mut self) -> Result<()> {
-        let cmd = Packet::new(&[0x12, 0x5A]);
-        self.uart
-            .write(&cmd.frame())
-            .chain_err(|| ErrorKind::CommandWrite("TOGGLE_HOLD"))?;
+        let cmd = self.send("TOGGLE_HOLD", &[0x12, 0x5A])?;
+        self.wait_success(&cmd)?;
+        self.state.toggle_hold();
+        Ok(())
+    }
+
+    /// Turn on/off HOLD.
+    ///
+    /// Unlike `toggle_hold` it emulates 'Hold' button only if HOLD state differs from `on`
+    /// and waits until measurements confirm the new state.
+    /// Monitoring mode should be turned on.
+    pub fn set_hold(&mut self, on: bool) -> Result<()> {
+        if self.get_fresh_measurement()?.is_holded() == on {
+            return Ok(());
+        }
+        self.toggle_hold()?;
 
-        self.wait_success()
+        // measurements read after the acknowledgement
+        let cmd = Command::new("SET_HOLD", &[]);
+        self.wait(&cmd, |msg| match msg {
+            Message::Measurement(ref m) if m.is_holded() == on => Ok(()),
+            msg => Err(msg.describe()),
+        })
     }
 
     /// Save current measurement in DMM memory.
     pub fn save_measurement(&mut self) -> Result<()> {
-        let cmd = Packet::new(&[0x06]);
-        self.uart
-            .write(&cmd.frame())
-            .chain_err(|| ErrorKind::CommandWrite("SAVE"))?;
-
-        self.wait_success()
+        let cmd = self.send("SAVE", &[0x06])?;
+        self.wait_success(&cmd)
     }
 
     /// Get saved measurement count.
     pub fn get_saved_measurement_count(&mut self) -> Result<u16> {
-        let cmd = Packet::new(&[0x08]);
-        self.uart
-            .write(&cmd.frame())
-            .chain_err(|| ErrorKind::CommandWrite("GET_SAVE_COUNT"))?;
-
-        let reply = self.wait_reply(0x08)?;
+        let cmd = self.send("GET_SAVE_COUNT", &[0x08])?;
+        let reply = self.wait_reply(&cmd, 0x08)?;
         Ok(LittleEndian::read_u16(&reply))
     }
 
     /// Get saved measurement.
     pub fn get_saved_measurement(&mut self, n: u16) -> Result<(NaiveDateTime, Measurement)> {
-        let mut cmd: [u8; 3] = [0x07, 0x00, 0x00];
-        LittleEndian::write_u16(&mut cmd[1..], n);
-        let pkt = Packet::new(&cmd);
+        let mut body: [u8; 3] = [0x07, 0x00, 0x00];
+        LittleEndian::write_u16(&mut body[1..], n);
+        let cmd = self.send("GET_SAVE", &body)?;
 
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("GET_SAVE"))?;
-
-        let (datetime, measurement) = self.wait_save()?;
+        let (datetime, measurement) = self.wait_save(&cmd)?;
 
         Ok((datetime, measurement))
     }
@@ -118,19 +206,15 @@ impl Dmm {
     ///
     /// `index` - save ID (1..0xFFFE).
     pub fn delete_saved_measurement(&mut self, index: u16) -> Result<()> {
-        if index < 1 || index > 0xFFFE {
+        if !(1..=0xFFFE).contains(&index) {
             return Err(ErrorKind::OutOfRange.into());
         }
 
-        let mut cmd: [u8; 3] = [0x09, 0x00, 0x00];
-        LittleEndian::write_u16(&mut cmd[1..], index);
-        let pkt = Packet::new(&cmd);
-
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("DELETE_SAVE"))?;
+        let mut body: [u8; 3] = [0x09, 0x00, 0x00];
+        LittleEndian::write_u16(&mut body[1..], index);
+        let cmd = self.send("DELETE_SAVE", &body)?;
 
-        self.wait_success()
+        self.wait_success(&cmd)
     }
 
     /// Delete all saved measurements.
@@ -144,15 +228,34 @@ impl Dmm {
     /// invoke `set_min_max_mode(true)` again.
     pub fn set_min_max_mode(&mut self, on: bool) -> Result<()> {
         let cmd = if on {
-            Packet::new(&[0x04, 0x01])
+            self.send("SET_MIN_MAX_MODE", &[0x04, 0x01])?
         } else {
-            Packet::new(&[0x04, 0x00])
+            self.send("SET_MIN_MAX_MODE", &[0x04, 0x00])?
         };
-        self.uart
-            .write(&cmd.frame())
-            .chain_err(|| ErrorKind::CommandWrite("SET_MIN_MAX_MODE"))?;
 
-        self.wait_success()
+        self.wait_success(&cmd)?;
+        self.state.is_min_max = Some(on);
+        if on {
+            self.min_max.start(self.state.mode, utils::now());
+        } else {
+            self.min_max.finish(utils::now());
+        }
+        Ok(())
+    }
+
+    /// Returns current min/max session.
+    ///
+    /// Sessions are tracked from `set_min_max_mode` calls and incoming measurements,
+    /// so monitoring mode should be turned on.
+    pub fn min_max_session(&self) -> Option<&MinMaxSession> {
+        self.min_max.current()
+    }
+
+    /// Takes min/max sessions finished (or reset) since the last call.
+    ///
+    /// Only the last 64 sessions are kept between calls.
+    pub fn take_min_max_sessions(&mut self) -> Vec<MinMaxSession> {
+        self.min_max.take_finished()
     }
 
     /// Set measuring range.
@@ -160,46 +263,156 @@ impl Dmm {
     /// Invalid step (`Range::Step8` in mVDC mode for example) switches DMM to next range.
     pub fn set_range(&mut self, range: Range) -> Result<()> {
         let b = range.to_bin();
-        let cmd = Packet::new(&[0x02, b]);
-        self.uart
-            .write(&cmd.frame())
-            .chain_err(|| ErrorKind::CommandWrite("SET_RANGE"))?;
+        let cmd = self.send("SET_RANGE", &[0x02, b])?;
+
+        self.wait_success(&cmd)?;
+        self.state.set_range(range, Instant::now());
+        Ok(())
+    }
 
-        self.wait_success()
+    /// Set measuring range and wait until measurements confirm it.
+    ///
+    /// Monitoring mode should be turned on.
+    /// Returns `ErrorKind::UnexpectedRange` if DMM switches to other range
+    /// (`Range::Step8` in mVDC mode for example)
+    /// and `ErrorKind::WaitTimeout` if it stays in the previous range.
+    pub fn set_range_and_wait(&mut self, range: Range, settling: Settling) -> Result<Measurement> {
+        let previous = (self.state.range, self.state.is_auto_range);
+        self.set_range(range)?;
+
+        let cmd = Command::new("SET_RANGE_AND_WAIT", &[]);
+        self.wait_switch(
+            &cmd,
+            settling,
+            |m| {
+                let is_previous = match previous {
+                    (_, None) => true,
+                    (_, Some(true)) => m.is_auto_range(),
+                    (previous_range, Some(false)) => {
+                        !m.is_auto_range() && Some(m.range()) == previous_range
+                    }
+                };
+                if range == Range::Auto && m.is_auto_range()
+                    || range != Range::Auto && !m.is_auto_range() && m.range() == range
+                {
+                    Switch::Done
+                } else if is_previous {
+                    Switch::Pending
+                } else {
+                    Switch::Failed
+                }
+            },
+            |m| ErrorKind::UnexpectedRange(m.mode(), range, m.range()).into(),
+        )
     }
 
     /// Set reference value in relative measuring mode.
+    ///
+    /// `val` is in the unit of current mode without metric prefix (volts in mVDC mode, etc).
     pub fn set_reference_value(&mut self, val: f32) -> Result<()> {
-        let mut cmd: [u8; 5] = [0x03, 0x00, 0x00, 0x00, 0x00];
-        LittleEndian::write_f32(&mut cmd[1..], val);
-        let pkt = Packet::new(&cmd);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("SET_REFERENCE"))?;
+        let mut body: [u8; 5] = [0x03, 0x00, 0x00, 0x00, 0x00];
+        LittleEndian::write_f32(&mut body[1..], val);
+        let cmd = self.send("SET_REFERENCE", &body)?;
+
+        self.wait_success(&cmd)?;
+        self.state.reference = Some(val);
+        Ok(())
+    }
+
+    /// Set reference value of relative measuring mode.
+    ///
+    /// Unlike `set_reference_value` it converts `reference` to the unit
+    /// of current mode (`1500 mV` is sent as `1.5` in mVDC mode, `68 °F` as `20` in °C mode, etc).
+    /// Monitoring mode should be turned on.
+    pub fn set_reference(&mut self, reference: &Value) -> Result<()> {
+        let unit = match self.get_fresh_measurement()? {
+            Measurement::Relative(ref m) => m.reference.unit.unit,
+            ref m => m.primary().unit.unit,
+        };
+        let val = reference.convert(UnitExp { unit, exponent: 0 })?.value;
+        self.set_reference_value(val)
+    }
+
+    /// Switch current mode to its relative counterpart
+    /// and take reference value from current reading.
+    ///
+    /// Monitoring mode should be turned on.
+    /// Returns the reference value.
+    pub fn zero_here(&mut self) -> Result<Value> {
+        let measurement = self.get_fresh_measurement()?;
+        let reference = match measurement {
+            Measurement::Relative(ref m) => m.measurement.clone(),
+            ref m => m.primary().clone(),
+        };
+        if reference.overload_neg || reference.overload_pos {
+            return Err(ErrorKind::OutOfRange.into());
+        }
 
-        self.wait_success()
+        let mode = measurement.mode();
+        let relative = mode
+            .with_relative(true)
+            .ok_or_else(|| Error::from(ErrorKind::NoRelativeMode(mode)))?;
+        if relative != mode {
+            self.set_mode_and_wait(relative, Settling::default())?;
+        }
+        self.set_reference(&reference)?;
+        Ok(reference)
+    }
+
+    /// Switch current mode to its absolute counterpart if relative mode is on.
+    pub fn clear_relative(&mut self) -> Result<()> {
+        let mode = match self.state.mode {
+            Some(mode) => mode,
+            None => self.get_measurement()?.mode(),
+        };
+        match mode.with_relative(false) {
+            Some(absolute) if absolute != mode => self.set_mode(absolute),
+            _ => Ok(()),
+        }
     }
 
     /// Set mode and submode.
     pub fn set_mode(&mut self, mode: Mode) -> Result<()> {
-        let mut cmd: [u8; 3] = [0x01, 0x00, 0x00];
-        cmd[1..].copy_from_slice(&mode.to_bin()[..]);
-        let pkt = Packet::new(&cmd);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("SET_MODE"))?;
+        let mut body: [u8; 3] = [0x01, 0x00, 0x00];
+        body[1..].copy_from_slice(&mode.to_bin()[..]);
+        let cmd = self.send("SET_MODE", &body)?;
+
+        self.wait_success(&cmd)?;
+        self.state.set_mode(mode, Instant::now());
+        Ok(())
+    }
+
+    /// Set mode and submode and wait until measurements confirm it.
+    ///
+    /// Monitoring mode should be turned on.
+    /// Returns `ErrorKind::UnexpectedMode` if DMM switches to other mode
+    /// and `ErrorKind::WaitTimeout` if it stays in the previous mode.
+    pub fn set_mode_and_wait(&mut self, mode: Mode, settling: Settling) -> Result<Measurement> {
+        let previous = self.state.mode;
+        self.set_mode(mode)?;
 
-        self.wait_success()
+        let cmd = Command::new("SET_MODE_AND_WAIT", &[]);
+        self.wait_switch(
+            &cmd,
+            settling,
+            |m| {
+                if m.mode() == mode {
+                    Switch::Done
+                } else if previous.is_none() || Some(m.mode()) == previous {
+                    Switch::Pending
+                } else {
+                    Switch::Failed
+                }
+            },
+            |m| ErrorKind::UnexpectedMode(mode, m.mode()).into(),
+        )
     }
 
     /// Get record count.
     pub fn get_record_count(&mut self) -> Result<u16> {
-        let cmd = Packet::new(&[0x0E]);
-        self.uart
-            .write(&cmd.frame())
-            .chain_err(|| ErrorKind::CommandWrite("GET_RECORDS_COUNT"))?;
+        let cmd = self.send("GET_RECORDS_COUNT", &[0x0E])?;
 
-        let reply = self.wait_reply(0x0E)?;
+        let reply = self.wait_reply(&cmd, 0x0E)?;
         Ok(LittleEndian::read_u16(&reply))
     }
 
@@ -210,285 +423,615 @@ impl Dmm {
         if i < 1 {
             return Err(ErrorKind::OutOfRange.into());
         }
-        let mut cmd: [u8; 3] = [0x0C, 0x00, 0x00];
-        LittleEndian::write_u16(&mut cmd[1..], i);
-        let pkt = Packet::new(&cmd);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("GET_RECORD_INFO"))?;
+        let mut body: [u8; 3] = [0x0C, 0x00, 0x00];
+        LittleEndian::write_u16(&mut body[1..], i);
+        let cmd = self.send("GET_RECORD_INFO", &body)?;
 
-        let reply = self.wait_record_info()?;
+        let reply = self.wait_record_info(&cmd)?;
         Ok(reply)
     }
 
     /// Get record samples.
     ///
     /// `i` is index of record (starting from 1).
+    /// It can be cancelled, see `download_record`.
     pub fn get_record_data(&mut self, i: u16) -> Result<Vec<RecordDataItem>> {
-        if i < 1 {
-            return Err(ErrorKind::OutOfRange.into());
-        }
-        let mut offset = 1;
-        let info = self.get_record_info(i)?;
         let mut items: Vec<RecordDataItem> = Vec::new();
-        let mut cmd: [u8; 7] = [0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
-        LittleEndian::write_u16(&mut cmd[1..], i);
-        loop {
-            LittleEndian::write_u32(&mut cmd[3..], offset as u32);
-            let pkt = Packet::new(&cmd);
-            self.uart
-                .write(&pkt.frame())
-                .chain_err(|| ErrorKind::CommandWrite("GET_RECORD_DATA"))?;
-
-            let raw_items = self.wait_record_data()?;
-            let raw_items_count = raw_items.len();
-            if raw_items_count == 0 {
-                return Ok(items);
+        self.download_record(i, 0, |chunk, _| items.extend(chunk))?;
+        Ok(items)
+    }
+
+    /// Download record samples chunk by chunk.
+    ///
+    /// `i` is index of record (starting from 1),
+    /// `start` is a number of samples to skip (to resume interrupted download),
+    /// `OutOfRange` is returned if it exceeds the number of samples in the record.
+    /// `on_chunk` is invoked with samples of each received packet and download progress.
+    /// Returns total number of received samples including skipped ones,
+    /// so the download can be resumed from last reported `RecordProgress::received`
+    /// after an error.
+    ///
+    /// Cancellation (see `CancellationToken`) is checked before requesting each chunk.
+    pub fn download_record<F>(&mut self, i: u16, start: u32, mut on_chunk: F) -> Result<u32>
+    where
+        F: FnMut(Vec<RecordDataItem>, RecordProgress),
+    {
+        self.cancellable(|dmm| {
+            if i < 1 {
+                return Err(ErrorKind::OutOfRange.into());
+            }
+            let info = dmm.get_record_info(i)?;
+            if start > info.sample_count {
+                return Err(ErrorKind::OutOfRange.into());
             }
-            items.extend(
-                raw_items
+            let mut progress = RecordProgress {
+                received: start,
+                total: info.sample_count,
+            };
+            let mut body: [u8; 7] = [0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+            LittleEndian::write_u16(&mut body[1..], i);
+            loop {
+                // sample offset is counted from 1
+                let offset = progress
+                    .received
+                    .checked_add(1)
+                    .ok_or_else(|| Error::from(ErrorKind::OutOfRange))?;
+                LittleEndian::write_u32(&mut body[3..], offset);
+                dmm.check_cancelled()?;
+                let cmd = dmm.send("GET_RECORD_DATA", &body)?;
+
+                let raw_items = dmm.wait_record_data(&cmd)?;
+                if raw_items.is_empty() {
+                    return Ok(progress.received);
+                }
+                progress.received = progress.received.saturating_add(raw_items.len() as u32);
+                let items = raw_items
                     .into_iter()
-                    .map(|item: message::RawRecordDataItem| {
-                        RecordDataItem {
-                            value: Value {
-                                overload_neg: item.overload_neg,
-                                overload_pos: item.overload_pos,
-                                value: item.value,
-                                precision: Some(item.precision),
-                                unit: info.unit,
-                            },
-                            timestamp: item.timestamp,
-                        }
-                    }),
-            );
-            offset += raw_items_count;
-        }
+                    .map(|item: message::RawRecordDataItem| RecordDataItem {
+                        value: Value {
+                            overload_neg: item.overload_neg,
+                            overload_pos: item.overload_pos,
+                            value: item.value,
+                            precision: Some(item.precision),
+                            unit: info.unit,
+                        },
+                        timestamp: item.timestamp,
+                    })
+                    .collect();
+                on_chunk(items, progress);
+            }
+        })
     }
 
     /// Start new recording
     /// with `name` (printable ASCII characters are allowed, 10 characters is maximum),
     /// `interval` in seconds (1..3600 second(s)),
     /// and `duration` in minutes (1..143999 minute(s)).
+    ///
+    /// See `start_recording` for typed parameters.
     pub fn start_record(&mut self, name: &str, interval: u16, duration: u32) -> Result<()> {
-        for c in name.chars() {
-            if !utils::allowed_char(c) {
-                return Err(ErrorKind::InvalidRecordName(name.into()).into());
-            }
-        }
-        if name.len() > 10 {
-            return Err(ErrorKind::RecordNameTooLong(name.into()).into());
-        }
-        if interval < 1 || interval > 3600 {
-            return Err(ErrorKind::RecordIntervalIsOutOfRange(interval).into());
-        }
-        if duration < 1 || duration > 143999 {
-            return Err(ErrorKind::RecordDurationIsOutOfRange(duration).into());
-        }
+        let config = RecordConfig::new(
+            RecordName::new(name)?,
+            Duration::from_secs(u64::from(interval)),
+            Duration::from_secs(u64::from(duration) * 60),
+        )?;
+        self.start_recording(&config)
+    }
 
-        let mut cmd: [u8; 18] = [0; 18];
-        cmd[0] = 0x0A;
-        let name_bytes = name.as_bytes();
-        cmd[1..(name_bytes.len() + 1)].copy_from_slice(name.as_bytes());
+    /// Start new recording with `config`.
+    pub fn start_recording(&mut self, config: &RecordConfig) -> Result<()> {
+        let mut body: [u8; 18] = [0; 18];
+        body[0] = 0x0A;
+        let name_bytes = config.name().as_str().as_bytes();
+        body[1..(name_bytes.len() + 1)].copy_from_slice(name_bytes);
 
-        LittleEndian::write_u16(&mut cmd[12..], interval);
-        LittleEndian::write_u32(&mut cmd[14..], duration);
+        LittleEndian::write_u16(&mut body[12..], config.interval_secs());
+        LittleEndian::write_u32(&mut body[14..], config.duration_minutes());
 
-        let pkt = Packet::new(&cmd);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("RECORD_START"))?;
+        let cmd = self.send("RECORD_START", &body)?;
 
-        self.wait_success()?;
+        self.wait_success(&cmd)?;
+        self.state.is_recording = Some(true);
+        self.record_config = Some(config.clone());
 
         Ok(())
     }
 
     /// Stop current recording.
     pub fn stop_record(&mut self) -> Result<()> {
-        let pkt = Packet::new(&[0x0B]);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("RECORD_STOP"))?;
-        self.wait_success()?;
+        let cmd = self.send("RECORD_STOP", &[0x0B])?;
+        self.wait_success(&cmd)?;
+        self.state.is_recording = Some(false);
         Ok(())
     }
 
+    /// Get recording status.
+    ///
+    /// It combines recording flag of current measurement with info of the newest record
+    /// and config of the last record started by `start_recording` if it's the newest one.
+    /// Monitoring mode should be turned on.
+    pub fn get_record_status(&mut self) -> Result<RecordStatus> {
+        let is_recording = self.get_measurement()?.is_recording();
+        let count = self.get_record_count()?;
+        let info = if count > 0 {
+            Some(self.get_record_info(count)?)
+        } else {
+            None
+        };
+        let config = match info {
+            Some(ref info) => self
+                .record_config
+                .clone()
+                .filter(|config| config.name().as_str() == info.name),
+            None => None,
+        };
+        Ok(RecordStatus {
+            is_recording,
+            info,
+            time: utils::now(),
+            config,
+        })
+    }
+
+    /// Wait until recording finishes and return final recording status.
+    ///
+    /// Monitoring mode should be turned on.
+    /// Use `CancellationToken` to stop waiting (it's checked while waiting for measurements).
+    pub fn wait_record_finished(&mut self) -> Result<RecordStatus> {
+        self.cancellable(|dmm| {
+            loop {
+                dmm.check_cancelled()?;
+                if !dmm.get_measurement()?.is_recording() {
+                    break;
+                }
+            }
+            dmm.get_record_status()
+        })
+    }
+
     /// Turn on monitoring mode.
     pub fn monitor_on(&mut self) -> Result<()> {
-        let pkt = Packet::new(&[0x05, 0x01]);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("MONITOR_ON"))?;
+        let cmd = self.send("MONITOR_ON", &[0x05, 0x01])?;
 
-        self.wait_measurement()?;
+        self.wait_measurement(&cmd)?;
+        self.state.set_monitoring(true, Instant::now());
         Ok(())
     }
 
     /// Turn off monitoring mode.
     pub fn monitor_off(&mut self) -> Result<()> {
-        let pkt = Packet::new(&[0x05, 0x00]);
-        self.uart
-            .write(&pkt.frame())
-            .chain_err(|| ErrorKind::CommandWrite("MONITOR_OFF"))?;
+        let cmd = self.send("MONITOR_OFF", &[0x05, 0x00])?;
 
-        self.wait_success_or_measurement()?;
+        self.wait_success_or_measurement(&cmd)?;
+        self.state.set_monitoring(false, Instant::now());
         Ok(())
     }
 
     /// Returns first message from DMM.
     ///
     /// This function blocks thread until to read a message
-    /// or exceeds `WAIT_TIMEOUT` duration.
-    pub(crate) fn read_message(&mut self) -> Result<Message> {
-        use std::cmp::max;
-        let now = Instant::now();
+    /// or `deadline` is reached (`None` is returned in this case).
+    pub(crate) fn read_message(&mut self, deadline: Instant) -> Result<Option<Message>> {
+        Ok(self.read_received(deadline, false)?.map(Received::into_message))
+    }
+
+    /// Returns first message from DMM with stamped measurement.
+    ///
+    /// If `interruptible` it also returns `None` as soon as cancellation is requested.
+    /// See `read_message`.
+    fn read_received(
+        &mut self,
+        deadline: Instant,
+        interruptible: bool,
+    ) -> Result<Option<Received>> {
         loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
+            if Instant::now() > deadline || interruptible && self.cancel.is_cancelled() {
+                return Ok(None);
             }
-            match Message::from_bin(&self.rx_buf)? {
+            match self.take_buffered_message()? {
                 None => {
-                    let mut buf: [u8; 64] = [0; 64];
-                    let read = self.uart.read(&mut buf)?;
-                    self.rx_buf.extend_from_slice(&buf[0..read]);
-                }
-                Some((msg, consumed)) => {
-                    let new_len = self.rx_buf.len() - consumed;
-                    let mut new_buf = Vec::with_capacity(max(new_len, RX_BUF_LENGTH));
-                    new_buf.resize(new_len, 0);
-                    new_buf.copy_from_slice(&self.rx_buf[consumed..]);
-                    self.rx_buf = new_buf;
-                    return Ok(msg);
+                    self.read_uart()?;
                 }
+                Some(received) => return Ok(Some(received)),
             }
         }
     }
 
-    /// Returns measurement from DMM.
-    ///
-    /// This function blocks thread until to read a message
-    /// or exceeds `WAIT_TIMEOUT` duration.
-    pub fn get_measurement(&mut self) -> Result<Measurement> {
-        self.wait_measurement()
+    /// Reads available bytes from UART to `rx_buf`.
+    fn read_uart(&mut self) -> Result<usize> {
+        let mut buf: [u8; 64] = [0; 64];
+        let read = self.uart.read(&mut buf)?;
+        if read > 0 {
+            self.rx_buf.extend_from_slice(&buf[0..read]);
+            self.rx_times.push_back((read, utils::now(), Instant::now()));
+        }
+        Ok(read)
     }
 
-    fn wait_reply(&mut self, cmd: u8) -> Result<Vec<u8>> {
-        let now = Instant::now();
-        loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
+    /// Reads bytes already received by UART with short timeout until there are no more.
+    fn drain_uart(&mut self) -> Result<()> {
+        self.uart.set_read_timeout(Duration::from_millis(DRAIN_READ_TIMEOUT));
+        let result = loop {
+            match self.read_uart() {
+                Ok(read) if read > 0 && self.rx_buf.len() < RX_BUF_LENGTH => {}
+                Ok(_) => break Ok(()),
+                Err(err) => break Err(err),
             }
-            if let Message::Reply(data) = msg {
-                if data[0] == cmd {
-                    let mut v = Vec::with_capacity(data.len() - 1);
-                    v.resize(data.len() - 1, 0);
-                    v.copy_from_slice(&data[1..]);
-                    return Ok(v);
-                }
+        };
+        self.uart.set_read_timeout(Duration::from_millis(READ_TIMEOUT));
+        result
+    }
+
+    /// Drops messages received so far (measurements still update the state).
+    fn discard_received(&mut self) -> Result<()> {
+        self.drain_uart()?;
+        while self.take_buffered_message()?.is_some() {}
+        Ok(())
+    }
+
+    /// Returns first message already received in `rx_buf` without reading UART.
+    fn take_buffered_message(&mut self) -> Result<Option<Received>> {
+        let (msg, consumed) = match Message::from_bin(&self.rx_buf)? {
+            Some(parsed) => parsed,
+            None => return Ok(None),
+        };
+        let (received, instant) = self.consume_rx(consumed);
+        let measurement = match msg {
+            Message::Measurement(measurement) => measurement,
+            msg => return Ok(Some(Received::Other(msg))),
+        };
+        self.state.update(&measurement, instant);
+        self.min_max.update(&measurement, received);
+        let sequence = self.sequence;
+        self.sequence += 1;
+        Ok(Some(Received::Measurement(TimestampedMeasurement {
+            measurement,
+            received,
+            instant,
+            sequence,
+        })))
+    }
+
+    /// Removes `consumed` bytes from `rx_buf`.
+    ///
+    /// Returns host time when the last of them has been read from UART.
+    fn consume_rx(&mut self, consumed: usize) -> (NaiveDateTime, Instant) {
+        self.rx_buf.drain(..consumed);
+        let mut arrival = None;
+        let mut left = consumed;
+        while left > 0 {
+            let chunk = match self.rx_times.front_mut() {
+                Some(chunk) => chunk,
+                None => break,
+            };
+            arrival = Some((chunk.1, chunk.2));
+            if chunk.0 > left {
+                chunk.0 -= left;
+                break;
             }
+            left -= chunk.0;
+            self.rx_times.pop_front();
         }
+        arrival.unwrap_or_else(|| (utils::now(), Instant::now()))
     }
 
-    fn wait_measurement(&mut self) -> Result<Measurement> {
-        let now = Instant::now();
-        loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
-            }
-            if let Message::Measurement(measurement) = msg {
-                return Ok(measurement);
+    /// Returns the first measurement already received from DMM.
+    ///
+    /// Unlike `get_measurement` it doesn't wait for new data,
+    /// it only reads bytes already received by UART (a few milliseconds).
+    /// Other buffered messages before the measurement are dropped.
+    pub fn try_get_measurement(&mut self) -> Result<Option<Measurement>> {
+        Ok(self.try_get_timestamped_measurement()?.map(|m| m.measurement))
+    }
+
+    /// Returns the first measurement already received from DMM with host reception time.
+    ///
+    /// See `try_get_measurement`.
+    pub fn try_get_timestamped_measurement(&mut self) -> Result<Option<TimestampedMeasurement>> {
+        self.drain_uart()?;
+        while let Some(received) = self.take_buffered_message()? {
+            if let Received::Measurement(m) = received {
+                return Ok(Some(m));
             }
         }
+        Ok(None)
     }
 
-    fn wait_success(&mut self) -> Result<()> {
+    /// Returns measurement from DMM or `None` if `timeout` is exceed.
+    ///
+    /// Timeout resolution is limited by UART read timeout (100 ms).
+    pub fn get_measurement_timeout(&mut self, timeout: Duration) -> Result<Option<Measurement>> {
+        Ok(self
+            .get_timestamped_measurement_timeout(timeout)?
+            .map(|m| m.measurement))
+    }
+
+    /// Returns measurement from DMM with host reception time or `None` if `timeout` is exceed.
+    ///
+    /// See `get_measurement_timeout`.
+    pub fn get_timestamped_measurement_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<TimestampedMeasurement>> {
         let now = Instant::now();
+        // too large timeout (Duration::MAX) is limited to ~136 years
+        let deadline = now
+            .checked_add(timeout)
+            .unwrap_or_else(|| now + Duration::from_secs(u64::from(u32::MAX)));
         loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
-            }
-            if let Message::Success = msg {
-                return Ok(());
+            match self.read_received(deadline, false)? {
+                Some(Received::Measurement(m)) => return Ok(Some(m)),
+                Some(Received::Other(_)) => {}
+                None => return Ok(None),
             }
         }
     }
 
-    fn wait_success_or_measurement(&mut self) -> Result<()> {
-        let now = Instant::now();
-        loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
-            }
-            if let Message::Success = msg {
-                return Ok(());
-            }
-            if let Message::Measurement(_) = msg {
-                return Ok(());
+    /// Drains already received messages and returns the newest measurement.
+    ///
+    /// It doesn't wait for new data, see `try_get_measurement`.
+    pub fn latest_measurement(&mut self) -> Result<Option<Measurement>> {
+        Ok(self.latest_timestamped_measurement()?.map(|m| m.measurement))
+    }
+
+    /// Drains already received messages and returns the newest measurement
+    /// with host reception time.
+    ///
+    /// See `latest_measurement`.
+    pub fn latest_timestamped_measurement(&mut self) -> Result<Option<TimestampedMeasurement>> {
+        self.drain_uart()?;
+        let mut latest = None;
+        while let Some(received) = self.take_buffered_message()? {
+            if let Received::Measurement(m) = received {
+                latest = Some(m);
             }
         }
+        Ok(latest)
     }
 
-    fn wait_save(&mut self) -> Result<(NaiveDateTime, Measurement)> {
-        let now = Instant::now();
-        loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
+    /// Returns measurement from DMM.
+    ///
+    /// This function blocks thread until to read a message
+    /// or exceeds `WAIT_TIMEOUT` duration.
+    pub fn get_measurement(&mut self) -> Result<Measurement> {
+        Ok(self.get_timestamped_measurement()?.measurement)
+    }
+
+    /// Discards messages already received and returns the next measurement.
+    fn get_fresh_measurement(&mut self) -> Result<Measurement> {
+        self.discard_received()?;
+        self.get_measurement()
+    }
+
+    /// Returns measurement from DMM with host reception time and sequence number.
+    ///
+    /// See `get_measurement`.
+    pub fn get_timestamped_measurement(&mut self) -> Result<TimestampedMeasurement> {
+        let cmd = Command::new("GET_MEASUREMENT", &[]);
+        self.wait_measurement(&cmd)
+    }
+
+    /// Logs measurements with host-side `logger` until an error occurs or it's cancelled.
+    ///
+    /// Monitoring mode should be turned on.
+    /// Cancellation (see `CancellationToken`) stops logging without error,
+    /// it's checked while waiting for measurements.
+    pub fn run_logger(&mut self, logger: &mut Logger) -> Result<()> {
+        let result = self.cancellable(|dmm| loop {
+            if dmm.cancel.is_cancelled() {
+                break Ok(());
             }
-            if let Message::Save(datetime, measurement) = msg {
-                return Ok((datetime, measurement));
+            match dmm.get_timestamped_measurement() {
+                Ok(m) => {
+                    if let Err(err) = logger.log(&m) {
+                        break Err(err);
+                    }
+                }
+                Err(Error(ErrorKind::Cancelled, _)) => break Ok(()),
+                Err(err) => break Err(err),
             }
+        });
+        logger.flush()?;
+        result
+    }
+
+    /// Runs cancellable operation `f` (see `CancellationToken`).
+    fn cancellable<T, F>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Dmm) -> Result<T>,
+    {
+        let cancel = self.cancel.clone();
+        cancel.run(|| {
+            self.is_cancellable = true;
+            let result = f(self);
+            self.is_cancellable = false;
+            result
+        })
+    }
+
+    /// Returns `Cancelled` error if cancellation is requested.
+    ///
+    /// It should be called only when no reply is pending, so DMM and `state` stay consistent.
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancel.is_cancelled() {
+            return Err(ErrorKind::Cancelled.into());
         }
+        Ok(())
     }
 
-    fn wait_record_info(&mut self) -> Result<RecordInfo> {
-        let now = Instant::now();
+    /// Writes command to DMM.
+    fn send(&mut self, name: &'static str, body: &[u8]) -> Result<Command> {
+        let mut cmd = Command::new(name, body);
+        self.uart
+            .write(&cmd.packet().frame())
+            .chain_err(|| ErrorKind::CommandWrite(cmd.context(&Unexpected::default())))?;
+        cmd.mark_sent();
+        Ok(cmd)
+    }
+
+    /// Reads messages until `accept` returns `Ok`.
+    ///
+    /// `accept` returns description of rejected message,
+    /// it's kept in error context as unexpected message.
+    fn wait<T, F>(&mut self, cmd: &Command, mut accept: F) -> Result<T>
+    where
+        F: FnMut(Message) -> std::result::Result<T, String>,
+    {
+        self.wait_received(cmd, |received| accept(received.into_message()))
+    }
+
+    /// Reads messages with stamped measurements until `accept` returns `Ok`.
+    ///
+    /// See `wait`.
+    fn wait_received<T, F>(&mut self, cmd: &Command, mut accept: F) -> Result<T>
+    where
+        F: FnMut(Received) -> std::result::Result<T, String>,
+    {
+        let deadline = Instant::now() + Duration::from_millis(WAIT_TIMEOUT);
+        // waiting for a measurement without request can be cancelled
+        let interruptible = self.is_cancellable && !cmd.is_sent();
+        let mut unexpected = Unexpected::default();
         loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
+            let received = match self
+                .read_received(deadline, interruptible)
+                .chain_err(|| ErrorKind::CommandRead(cmd.context(&unexpected)))?
+            {
+                Some(received) => received,
+                None if interruptible && self.cancel.is_cancelled() => {
+                    return Err(ErrorKind::Cancelled.into())
+                }
+                None => return Err(ErrorKind::WaitTimeout(cmd.context(&unexpected)).into()),
+            };
+            if let Received::Other(Message::Error) = received {
+                return Err(ErrorKind::CommandError(cmd.context(&unexpected)).into());
             }
-            if let Message::RecordInfo(info) = msg {
-                return Ok(info);
+            match accept(received) {
+                Ok(v) => return Ok(v),
+                Err(description) => unexpected.push(description),
             }
         }
     }
 
-    fn wait_record_data(&mut self) -> Result<Vec<message::RawRecordDataItem>> {
-        let now = Instant::now();
+    /// Reads measurements until DMM is settled in requested state.
+    ///
+    /// `classify` checks a measurement against the requested state,
+    /// `failure` builds an error if DMM is settled in other state.
+    fn wait_switch<C, F>(
+        &mut self,
+        cmd: &Command,
+        settling: Settling,
+        classify: C,
+        failure: F,
+    ) -> Result<Measurement>
+    where
+        C: Fn(&Measurement) -> Switch,
+        F: Fn(&Measurement) -> Error,
+    {
+        // every confirming measurement can take up to `MEASUREMENT_PERIOD`
+        let confirming = MEASUREMENT_PERIOD.saturating_mul(settling.stable_count as u64);
+        let deadline = Instant::now()
+            + Duration::from_millis(WAIT_TIMEOUT.saturating_add(confirming))
+            + settling.time;
+        let mut unexpected = Unexpected::default();
+        let mut stable = 0;
+        // consecutive measurements in other state, a single one can be transitional
+        let mut failed = 0;
+        let required_failures = settling.stable_count.max(2);
+        // last measurement in other state if DMM hasn't returned to the previous one since
+        let mut last_failed: Option<Measurement> = None;
         loop {
-            if now.elapsed() > Duration::from_millis(WAIT_TIMEOUT) {
-                return Err(ErrorKind::WaitTimeout.into());
-            }
-            let msg = self.read_message()?;
-            if let Message::Error = msg {
-                return Err(ErrorKind::CommandError.into());
-            }
-            if let Message::RecordData(items) = msg {
-                return Ok(items);
+            let measurement = match self
+                .read_message(deadline)
+                .chain_err(|| ErrorKind::CommandRead(cmd.context(&unexpected)))?
+            {
+                Some(Message::Measurement(measurement)) => measurement,
+                Some(Message::Error) => {
+                    return Err(ErrorKind::CommandError(cmd.context(&unexpected)).into())
+                }
+                Some(msg) => {
+                    unexpected.push(msg.describe());
+                    continue;
+                }
+                None => {
+                    return Err(match last_failed {
+                        Some(ref m) => failure(m),
+                        None => ErrorKind::WaitTimeout(cmd.context(&unexpected)).into(),
+                    })
+                }
+            };
+            let is_settled = cmd.elapsed() >= settling.time;
+            match classify(&measurement) {
+                Switch::Done => {
+                    stable += 1;
+                    if is_settled && stable >= settling.stable_count {
+                        return Ok(measurement);
+                    }
+                    failed = 0;
+                    last_failed = None;
+                }
+                Switch::Pending => {
+                    stable = 0;
+                    failed = 0;
+                    last_failed = None;
+                }
+                Switch::Failed => {
+                    stable = 0;
+                    failed += 1;
+                    if is_settled && failed >= required_failures {
+                        return Err(failure(&measurement));
+                    }
+                    last_failed = Some(measurement);
+                }
             }
         }
     }
+
+    fn wait_reply(&mut self, cmd: &Command, code: u8) -> Result<Vec<u8>> {
+        self.wait(cmd, |msg| match msg {
+            Message::Reply(ref data) if data.first() == Some(&code) => Ok(data[1..].to_vec()),
+            msg => Err(msg.describe()),
+        })
+    }
+
+    fn wait_measurement(&mut self, cmd: &Command) -> Result<TimestampedMeasurement> {
+        self.wait_received(cmd, |received| match received {
+            Received::Measurement(m) => Ok(m),
+            Received::Other(msg) => Err(msg.describe()),
+        })
+    }
+
+    fn wait_success(&mut self, cmd: &Command) -> Result<()> {
+        self.wait(cmd, |msg| match msg {
+            Message::Success => Ok(()),
+            msg => Err(msg.describe()),
+        })
+    }
+
+    fn wait_success_or_measurement(&mut self, cmd: &Command) -> Result<()> {
+        self.wait(cmd, |msg| match msg {
+            Message::Success | Message::Measurement(_) => Ok(()),
+            msg => Err(msg.describe()),
+        })
+    }
+
+    fn wait_save(&mut self, cmd: &Command) -> Result<(NaiveDateTime, Measurement)> {
+        self.wait(cmd, |msg| match msg {
+            Message::Save(datetime, measurement) => Ok((datetime, measurement)),
+            msg => Err(msg.describe()),
+        })
+    }
+
+    fn wait_record_info(&mut self, cmd: &Command) -> Result<RecordInfo> {
+        self.wait(cmd, |msg| match msg {
+            Message::RecordInfo(info) => Ok(info),
+            msg => Err(msg.describe()),
+        })
+    }
+
+    fn wait_record_data(&mut self, cmd: &Command) -> Result<Vec<message::RawRecordDataItem>> {
+        self.wait(cmd, |msg| match msg {
+            Message::RecordData(items) => Ok(items),
+            msg => Err(msg.describe()),
+        })
+    }
 }
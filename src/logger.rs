@@ -0,0 +1,376 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDateTime;
+
+use error::*;
+use mode::Mode;
+use timestamped::TimestampedMeasurement;
+use unit::{Unit, UnitStyle};
+
+/// Default maximum log file size (10 MiB).
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// How measurements are written to log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sampling {
+    /// Write every received measurement.
+    EveryPacket,
+
+    /// Write a measurement at most once per interval.
+    Interval(Duration),
+
+    /// Write min/max/mean of measurements per window.
+    Aggregate(Duration),
+}
+
+/// Host-side logger settings.
+#[derive(Clone, Debug)]
+pub struct LoggerConfig {
+    /// Directory of log files.
+    pub directory: PathBuf,
+
+    /// Session name, log files are named `<session>.<NNNN>.csv`.
+    pub session: String,
+
+    pub sampling: Sampling,
+
+    /// Log file is rotated when its size exceeds the limit.
+    pub max_file_size: u64,
+}
+
+impl LoggerConfig {
+    /// Creates settings with default file size limit (10 MiB).
+    pub fn new<P>(directory: P, session: &str, sampling: Sampling) -> LoggerConfig
+    where
+        P: Into<PathBuf>,
+    {
+        LoggerConfig {
+            directory: directory.into(),
+            session: session.into(),
+            sampling,
+            max_file_size: MAX_FILE_SIZE,
+        }
+    }
+}
+
+/// Measurements collected in current aggregation window.
+struct Window {
+    start: NaiveDateTime,
+    started: Instant,
+    mode: Mode,
+    unit: Unit,
+    count: usize,
+    overloads: usize,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+/// Writes primary values of measurements to CSV files.
+///
+/// Values are written with digits displayed by DMM and unit with metric prefix ("12.3,mVDC"),
+/// min/max/mean of aggregated values are written in SI units without metric prefix
+/// ("0.0123,VDC") since the range can change within a window.
+/// Overloaded values are written as `OL`/`-OL`.
+/// Every line is flushed to the file once it's written.
+/// Opening a logger of existing session appends to its last file.
+pub struct Logger {
+    config: LoggerConfig,
+    file: BufWriter<File>,
+    file_index: u32,
+    file_size: u64,
+    last_written: Option<Instant>,
+    window: Option<Window>,
+}
+
+impl Logger {
+    /// Opens the last log file of the session or creates a new one.
+    pub fn open(config: LoggerConfig) -> Result<Logger> {
+        fs::create_dir_all(&config.directory)?;
+        let prefix = format!("{}.", config.session);
+        let mut file_index = 0;
+        for entry in fs::read_dir(&config.directory)? {
+            let name = entry?.file_name();
+            let index = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(&prefix))
+                .and_then(|name| name.strip_suffix(".csv"))
+                .and_then(|index| index.parse::<u32>().ok());
+            if let Some(index) = index {
+                file_index = file_index.max(index);
+            }
+        }
+        let (file, file_size) = Logger::open_file(&config, file_index)?;
+        let mut logger = Logger {
+            config,
+            file,
+            file_index,
+            file_size,
+            last_written: None,
+            window: None,
+        };
+        if logger.file_size == 0 {
+            logger.write_header()?;
+        }
+        Ok(logger)
+    }
+
+    fn path(config: &LoggerConfig, index: u32) -> PathBuf {
+        config
+            .directory
+            .join(format!("{}.{:04}.csv", config.session, index))
+    }
+
+    fn open_file(config: &LoggerConfig, index: u32) -> Result<(BufWriter<File>, u64)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Logger::path(config, index))?;
+        let size = file.metadata()?.len();
+        Ok((BufWriter::new(file), size))
+    }
+
+    /// Returns path of current log file.
+    pub fn current_path(&self) -> PathBuf {
+        Logger::path(&self.config, self.file_index)
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let header = match self.config.sampling {
+            Sampling::Aggregate(_) => "time,mode,count,overloads,min,max,mean,unit",
+            _ => "time,sequence,mode,value,unit",
+        };
+        self.write_line(header)
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        if self.file_size >= self.config.max_file_size {
+            self.file.flush()?;
+            self.file_index += 1;
+            let (file, file_size) = Logger::open_file(&self.config, self.file_index)?;
+            self.file = file;
+            self.file_size = file_size;
+            if self.file_size == 0 {
+                self.write_header()?;
+            }
+        }
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+        self.file_size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Logs measurement according to sampling settings.
+    pub fn log(&mut self, m: &TimestampedMeasurement) -> Result<()> {
+        match self.config.sampling {
+            Sampling::EveryPacket => self.write_measurement(m),
+            Sampling::Interval(interval) => match self.last_written {
+                Some(last) if m.instant.duration_since(last) < interval => Ok(()),
+                _ => {
+                    self.last_written = Some(m.instant);
+                    self.write_measurement(m)
+                }
+            },
+            Sampling::Aggregate(window) => self.aggregate(m, window),
+        }
+    }
+
+    fn write_measurement(&mut self, m: &TimestampedMeasurement) -> Result<()> {
+        let value = m.measurement.primary();
+        let formatted = if value.overload_pos {
+            "OL".into()
+        } else if value.overload_neg {
+            "-OL".into()
+        } else {
+            match value.to_decimal() {
+                Some(decimal) => decimal.to_string(),
+                None => value.value.to_string(),
+            }
+        };
+        let line = format!(
+            "{},{},\"{}\",{},{}",
+            m.received.format(DATETIME_FORMAT),
+            m.sequence,
+            m.measurement.mode(),
+            formatted,
+            value.unit.display(UnitStyle::Ascii)
+        );
+        self.write_line(&line)
+    }
+
+    fn aggregate(&mut self, m: &TimestampedMeasurement, window: Duration) -> Result<()> {
+        let mode = m.measurement.mode();
+        let value = m.measurement.primary();
+        let is_finished = match self.window {
+            Some(ref w) => {
+                m.instant.duration_since(w.started) >= window
+                    || w.mode != mode
+                    || w.unit != value.unit.unit
+            }
+            None => false,
+        };
+        if is_finished {
+            self.finish_window()?;
+        }
+        let w = self.window.get_or_insert(Window {
+            start: m.received,
+            started: m.instant,
+            mode,
+            unit: value.unit.unit,
+            count: 0,
+            overloads: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+        });
+        w.count += 1;
+        let si = value.si();
+        if si.is_infinite() {
+            w.overloads += 1;
+        } else {
+            w.min = w.min.min(si);
+            w.max = w.max.max(si);
+            w.sum += si;
+        }
+        Ok(())
+    }
+
+    /// Writes current aggregation window.
+    fn finish_window(&mut self) -> Result<()> {
+        let w = match self.window.take() {
+            Some(w) => w,
+            None => return Ok(()),
+        };
+        let valid = w.count - w.overloads;
+        let line = if valid > 0 {
+            format!(
+                "{},\"{}\",{},{},{},{},{},{}",
+                w.start.format(DATETIME_FORMAT),
+                w.mode,
+                w.count,
+                w.overloads,
+                w.min,
+                w.max,
+                w.sum / valid as f64,
+                w.unit.symbol(UnitStyle::Ascii)
+            )
+        } else {
+            format!(
+                "{},\"{}\",{},{},,,,{}",
+                w.start.format(DATETIME_FORMAT),
+                w.mode,
+                w.count,
+                w.overloads,
+                w.unit.symbol(UnitStyle::Ascii)
+            )
+        };
+        self.write_line(&line)
+    }
+
+    /// Writes pending aggregation window.
+    pub fn flush(&mut self) -> Result<()> {
+        self.finish_window()?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    use chrono::NaiveDate;
+
+    use super::{Logger, LoggerConfig, Sampling};
+    use measurement::{Measurement, NormalMeasurement};
+    use mode::Mode;
+    use timestamped::TimestampedMeasurement;
+    use unit::Unit;
+    use value::Value;
+
+    fn measurement(sequence: u64, instant: Instant, value: f32) -> TimestampedMeasurement {
+        let value = Value::new(value, 1, Unit::VDC, -3);
+        TimestampedMeasurement {
+            measurement: Measurement::Normal(NormalMeasurement::new(
+                Mode::mVDC_Normal,
+                value,
+                None,
+                None,
+            )),
+            received: NaiveDate::from_ymd_opt(2020, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, sequence as u32)
+                .unwrap(),
+            instant: instant + Duration::from_secs(sequence),
+            sequence,
+        }
+    }
+
+    #[test]
+    fn test_logger() {
+        let directory =
+            ::std::env::temp_dir().join(format!("ut181a-logger-{}", ::std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let instant = Instant::now();
+
+        let sampling = Sampling::Aggregate(Duration::from_secs(2));
+        let mut config = LoggerConfig::new(&directory, "test", sampling);
+        config.max_file_size = 200;
+        {
+            let mut logger = Logger::open(config.clone()).unwrap();
+            for (i, &value) in [1.0, 3.0, 5.0].iter().enumerate() {
+                logger.log(&measurement(i as u64, instant, value)).unwrap();
+            }
+            // the first window is already flushed
+            let text = fs::read_to_string(logger.current_path()).unwrap();
+            assert_eq!(text.lines().count(), 2);
+        }
+        let text = fs::read_to_string(directory.join("test.0000.csv")).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "2020-01-01 00:00:00.000,\"mVDC\",2,0,0.001,0.003,0.002,VDC");
+        assert!(lines[2].starts_with("2020-01-01 00:00:02.000,\"mVDC\",1,0,0.005"));
+
+        // restart appends to the last file, then it's rotated
+        {
+            let mut logger = Logger::open(config).unwrap();
+            logger.log(&measurement(3, instant, 7.0)).unwrap();
+            logger.flush().unwrap();
+            logger.log(&measurement(6, instant, 9.0)).unwrap();
+            logger.flush().unwrap();
+            assert!(logger.current_path().ends_with("test.0001.csv"));
+        }
+        let text = fs::read_to_string(directory.join("test.0000.csv")).unwrap();
+        assert_eq!(text.lines().count(), 4);
+        let text = fs::read_to_string(directory.join("test.0001.csv")).unwrap();
+        assert_eq!(text.lines().count(), 2);
+
+        let config = LoggerConfig::new(&directory, "packets", Sampling::EveryPacket);
+        let mut logger = Logger::open(config).unwrap();
+        logger.log(&measurement(0, instant, 12.3)).unwrap();
+        let mut overloaded = measurement(1, instant, 0.0);
+        if let Measurement::Normal(ref mut m) = overloaded.measurement {
+            m.main.overload_neg = true;
+        }
+        logger.log(&overloaded).unwrap();
+        let text = fs::read_to_string(logger.current_path()).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[1], "2020-01-01 00:00:00.000,0,\"mVDC\",12.3,mVDC");
+        assert_eq!(lines[2], "2020-01-01 00:00:01.000,1,\"mVDC\",-OL,mVDC");
+        drop(logger);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -17,10 +17,12 @@ enum MeasurementKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NormalMeasurement {
     pub mode: Mode,
     pub is_holded: bool,
     pub is_auto_range: bool,
+    pub is_recording: bool,
     pub range: Range,
     pub main: Value,
     pub aux1: Option<Value>,
@@ -29,10 +31,12 @@ pub struct NormalMeasurement {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RelativeMeasurement {
     pub mode: Mode,
     pub is_holded: bool,
     pub is_auto_range: bool,
+    pub is_recording: bool,
     pub range: Range,
     pub relative: Value,
     pub reference: Value,
@@ -41,31 +45,40 @@ pub struct RelativeMeasurement {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MinMaxMeasurement {
     pub mode: Mode,
     pub is_holded: bool,
     pub is_auto_range: bool,
+    pub is_recording: bool,
     pub range: Range,
     pub main: Value,
     pub max: Value,
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
     pub max_time: Duration,
     pub average: Value,
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
     pub average_time: Duration,
     pub min: Value,
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
     pub min_time: Duration,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PeakMeasurement {
     pub mode: Mode,
     pub is_holded: bool,
     pub is_auto_range: bool,
+    pub is_recording: bool,
     pub range: Range,
     pub min: Value,
     pub max: Value,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum Measurement {
     Normal(NormalMeasurement),
     Relative(RelativeMeasurement),
@@ -78,6 +91,94 @@ fn read_duration(data: &[u8]) -> Duration {
 }
 
 impl Measurement {
+    /// Mode and submode.
+    pub fn mode(&self) -> Mode {
+        match *self {
+            Measurement::Normal(ref m) => m.mode,
+            Measurement::Relative(ref m) => m.mode,
+            Measurement::MinMax(ref m) => m.mode,
+            Measurement::Peak(ref m) => m.mode,
+        }
+    }
+
+    /// Measuring range.
+    pub fn range(&self) -> Range {
+        match *self {
+            Measurement::Normal(ref m) => m.range,
+            Measurement::Relative(ref m) => m.range,
+            Measurement::MinMax(ref m) => m.range,
+            Measurement::Peak(ref m) => m.range,
+        }
+    }
+
+    /// Is auto range selection on?
+    pub fn is_auto_range(&self) -> bool {
+        match *self {
+            Measurement::Normal(ref m) => m.is_auto_range,
+            Measurement::Relative(ref m) => m.is_auto_range,
+            Measurement::MinMax(ref m) => m.is_auto_range,
+            Measurement::Peak(ref m) => m.is_auto_range,
+        }
+    }
+
+    /// Is HOLD on?
+    pub fn is_holded(&self) -> bool {
+        match *self {
+            Measurement::Normal(ref m) => m.is_holded,
+            Measurement::Relative(ref m) => m.is_holded,
+            Measurement::MinMax(ref m) => m.is_holded,
+            Measurement::Peak(ref m) => m.is_holded,
+        }
+    }
+
+    /// Is recording in progress?
+    pub fn is_recording(&self) -> bool {
+        match *self {
+            Measurement::Normal(ref m) => m.is_recording,
+            Measurement::Relative(ref m) => m.is_recording,
+            Measurement::MinMax(ref m) => m.is_recording,
+            Measurement::Peak(ref m) => m.is_recording,
+        }
+    }
+
+    /// Headline value (main, relative, current or maximum value).
+    pub fn primary(&self) -> &Value {
+        match *self {
+            Measurement::Normal(ref m) => &m.main,
+            Measurement::Relative(ref m) => &m.relative,
+            Measurement::MinMax(ref m) => &m.main,
+            Measurement::Peak(ref m) => &m.max,
+        }
+    }
+
+    /// Iterates over all present values with their field names.
+    pub fn values<'a>(&'a self) -> impl Iterator<Item = (&'static str, &'a Value)> + 'a {
+        let values: Vec<(&'static str, Option<&'a Value>)> = match *self {
+            Measurement::Normal(ref m) => vec![
+                ("main", Some(&m.main)),
+                ("aux1", m.aux1.as_ref()),
+                ("aux2", m.aux2.as_ref()),
+                ("fast", m.fast.as_ref()),
+            ],
+            Measurement::Relative(ref m) => vec![
+                ("relative", Some(&m.relative)),
+                ("reference", Some(&m.reference)),
+                ("measurement", Some(&m.measurement)),
+                ("fast", m.fast.as_ref()),
+            ],
+            Measurement::MinMax(ref m) => vec![
+                ("main", Some(&m.main)),
+                ("max", Some(&m.max)),
+                ("average", Some(&m.average)),
+                ("min", Some(&m.min)),
+            ],
+            Measurement::Peak(ref m) => vec![("max", Some(&m.max)), ("min", Some(&m.min))],
+        };
+        values
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+    }
+
     pub(crate) fn from_bin(data: &[u8]) -> Result<Measurement> {
         let kind = match data[0] & 0x70 {
             0x00 => MeasurementKind::Normal,
@@ -89,7 +190,8 @@ impl Measurement {
 
         let is_fast = (data[0] & 0x08) != 0;
         let is_holded = (data[0] & 0x80) != 0;
-        let is_auto_range = data[1] == 1;
+        let is_auto_range = (data[1] & 0x01) != 0;
+        let is_recording = (data[1] & 0x20) != 0;
         let mode = Mode::from_bin(&data[2..])?;
         let range = Range::from_bin(&data[4..])?;
 
@@ -126,6 +228,7 @@ impl Measurement {
                     mode,
                     is_holded,
                     is_auto_range,
+                    is_recording,
                     range,
                     main,
                     aux1,
@@ -149,6 +252,7 @@ impl Measurement {
                     mode,
                     is_holded,
                     is_auto_range,
+                    is_recording,
                     range,
                     relative,
                     reference,
@@ -170,6 +274,7 @@ impl Measurement {
                     mode,
                     is_holded,
                     is_auto_range,
+                    is_recording,
                     range,
                     main,
                     max,
@@ -188,6 +293,7 @@ impl Measurement {
                     mode,
                     is_holded,
                     is_auto_range,
+                    is_recording,
                     range,
                     min,
                     max,
@@ -196,3 +302,112 @@ impl Measurement {
         }
     }
 }
+
+#[cfg(test)]
+impl NormalMeasurement {
+    /// Creates auto range measurement without bargraph value for tests.
+    pub(crate) fn new(
+        mode: Mode,
+        main: Value,
+        aux1: Option<Value>,
+        aux2: Option<Value>,
+    ) -> NormalMeasurement {
+        NormalMeasurement {
+            mode,
+            is_holded: false,
+            is_auto_range: true,
+            is_recording: false,
+            range: Range::Auto,
+            main,
+            aux1,
+            aux2,
+            fast: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl MinMaxMeasurement {
+    /// Creates auto range measurement with max, average and min equal to `main`
+    /// and zero times for tests.
+    pub(crate) fn new(mode: Mode, main: Value) -> MinMaxMeasurement {
+        MinMaxMeasurement {
+            mode,
+            is_holded: false,
+            is_auto_range: true,
+            is_recording: false,
+            range: Range::Auto,
+            max: main.clone(),
+            max_time: Duration::from_secs(0),
+            average: main.clone(),
+            average_time: Duration::from_secs(0),
+            min: main.clone(),
+            min_time: Duration::from_secs(0),
+            main,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Measurement;
+    use mode::Mode;
+    use range::Range;
+
+    #[test]
+    fn test_normal_misc2_flags() {
+        let data = [
+            0x00, 0x21, 0x11, 0x31, 0x02, // misc, misc2, mode, range
+            0x00, 0x00, 0xC0, 0x3F, 0x30, b'V', b'D', b'C', 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let measurement = Measurement::from_bin(&data).unwrap();
+        assert_eq!(measurement.mode(), Mode::VDC_Normal);
+        assert_eq!(measurement.primary().value, 1.5);
+        let names: Vec<_> = measurement.values().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["main"]);
+        match measurement {
+            Measurement::Normal(m) => {
+                assert_eq!(m.mode, Mode::VDC_Normal);
+                assert_eq!(m.range, Range::Step2);
+                assert!(m.is_auto_range);
+                assert!(m.is_recording);
+                assert!(!m.is_holded);
+                assert_eq!(m.main.value, 1.5);
+                assert_eq!(m.main.precision, Some(3));
+            }
+            m => panic!("unexpected measurement: {:?}", m),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        use std::time::Duration;
+
+        use super::MinMaxMeasurement;
+        use unit::Unit;
+        use value::Value;
+
+        let value = |value| Value::new(value, 1, Unit::Celsius, 0);
+        let mut m = MinMaxMeasurement::new(Mode::TempC_T1_T2, value(21.5));
+        m.max = value(22.0);
+        m.max_time = Duration::from_secs(60);
+        m.average = value(21.75);
+        m.average_time = Duration::from_secs(90);
+        m.min_time = Duration::from_secs(3);
+        let m = Measurement::MinMax(m);
+        let json = ::serde_json::to_value(&m).unwrap();
+        assert_eq!(json["kind"], "MinMax");
+        assert_eq!(json["mode"], "TempC_T1_T2");
+        assert_eq!(json["range"], "Auto");
+        assert_eq!(json["max_time"], 60);
+        assert_eq!(json["max"]["unit"]["unit"], "Celsius");
+        assert_eq!(json["max"]["unit"]["exponent"], 0);
+        assert_eq!(json["max"]["precision"], 1);
+
+        match ::serde_json::from_value::<Measurement>(json).unwrap() {
+            Measurement::MinMax(m) => assert_eq!(m.average_time, Duration::from_secs(90)),
+            m => panic!("unexpected measurement: {:?}", m),
+        }
+    }
+}
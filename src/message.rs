@@ -78,15 +78,28 @@ impl Message {
                 Ok(Message::RecordData(items))
             }
             0x72 => {
-                let mut vec = Vec::with_capacity(data.len() - 1);
-                vec.resize(data.len() - 1, 0);
-                vec.copy_from_slice(&data[1..]);
-                Ok(Message::Reply(vec))
+                Ok(Message::Reply(data[1..].to_vec()))
             }
             fmt => Err(ErrorKind::UnknownMessageFormat(fmt).into()),
         }
     }
 
+    /// Returns short description of the message.
+    pub(crate) fn describe(&self) -> String {
+        match *self {
+            Message::Success => "OK".into(),
+            Message::Error => "ER".into(),
+            Message::Measurement(_) => "measurement".into(),
+            Message::Save(datetime, _) => format!("saved measurement ({})", datetime),
+            Message::Reply(ref data) => match data.first() {
+                Some(cmd) => format!("reply to 0x{:02X}", cmd),
+                None => "empty reply".into(),
+            },
+            Message::RecordInfo(ref info) => format!("record info '{}'", info.name),
+            Message::RecordData(ref items) => format!("record data ({} sample(s))", items.len()),
+        }
+    }
+
     /// Return a message and a number of consumed bytes.
     pub(crate) fn from_bin(data: &[u8]) -> Result<Option<(Message, usize)>> {
         use nom::IResult;
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use chrono::{self, NaiveDateTime};
+
+use measurement::{Measurement, MinMaxMeasurement};
+use mode::Mode;
+
+/// Maximum number of finished sessions kept until `MinMaxTracker::take_finished`.
+const MAX_FINISHED_SESSIONS: usize = 64;
+
+/// Host-side record of a min/max mode session.
+///
+/// DMM reports min/max times relative to the session start only,
+/// so the start is tracked on the host with wall-clock time.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MinMaxSession {
+    /// Mode of the session.
+    pub mode: Mode,
+
+    /// Host time when the session has been started.
+    ///
+    /// It's estimated from the first measurement
+    /// if min/max mode was turned on without `Dmm::set_min_max_mode`.
+    pub started: NaiveDateTime,
+
+    /// Host time when the session has been finished or reset.
+    pub finished: Option<NaiveDateTime>,
+
+    /// Last min/max measurement of the session.
+    pub last: Option<MinMaxMeasurement>,
+}
+
+fn add(datetime: NaiveDateTime, duration: Duration) -> NaiveDateTime {
+    datetime + chrono::Duration::from_std(duration).unwrap_or_else(|_| chrono::Duration::zero())
+}
+
+impl MinMaxSession {
+    fn new(mode: Mode, started: NaiveDateTime) -> MinMaxSession {
+        MinMaxSession {
+            mode,
+            started,
+            finished: None,
+            last: None,
+        }
+    }
+
+    /// Host time when the maximum value has been measured.
+    pub fn max_at(&self) -> Option<NaiveDateTime> {
+        self.last.as_ref().map(|m| add(self.started, m.max_time))
+    }
+
+    /// Host time when the minimum value has been measured.
+    pub fn min_at(&self) -> Option<NaiveDateTime> {
+        self.last.as_ref().map(|m| add(self.started, m.min_time))
+    }
+
+    /// Session duration according to DMM.
+    pub fn duration(&self) -> Option<Duration> {
+        self.last.as_ref().map(|m| m.average_time)
+    }
+}
+
+/// Tracks min/max sessions from commands and incoming measurements.
+#[derive(Default)]
+pub(crate) struct MinMaxTracker {
+    current: Option<MinMaxSession>,
+    finished: Vec<MinMaxSession>,
+}
+
+impl MinMaxTracker {
+    pub(crate) fn current(&self) -> Option<&MinMaxSession> {
+        self.current.as_ref()
+    }
+
+    pub(crate) fn take_finished(&mut self) -> Vec<MinMaxSession> {
+        ::std::mem::take(&mut self.finished)
+    }
+
+    /// Records (re)start of min/max mode by command.
+    pub(crate) fn start(&mut self, mode: Option<Mode>, now: NaiveDateTime) {
+        let mode = mode.or_else(|| self.current.as_ref().map(|s| s.mode));
+        self.finish(now);
+        if let Some(mode) = mode {
+            self.current = Some(MinMaxSession::new(mode, now));
+        }
+    }
+
+    /// Records end of min/max mode.
+    ///
+    /// The oldest finished session is dropped if there are `MAX_FINISHED_SESSIONS` of them.
+    pub(crate) fn finish(&mut self, now: NaiveDateTime) {
+        if let Some(mut session) = self.current.take() {
+            session.finished = Some(now);
+            if self.finished.len() >= MAX_FINISHED_SESSIONS {
+                self.finished.remove(0);
+            }
+            self.finished.push(session);
+        }
+    }
+
+    pub(crate) fn update(&mut self, measurement: &Measurement, now: NaiveDateTime) {
+        let m = match *measurement {
+            Measurement::MinMax(ref m) => m,
+            _ => {
+                // measurements sent before min/max mode is turned on don't finish the session
+                if self.current.as_ref().is_some_and(|s| s.last.is_some()) {
+                    self.finish(now);
+                }
+                return;
+            }
+        };
+        let is_reset = match self.current {
+            Some(ref session) => {
+                session.mode != m.mode
+                    || session
+                        .last
+                        .as_ref()
+                        .is_some_and(|last| m.average_time < last.average_time)
+            }
+            None => true,
+        };
+        if is_reset {
+            self.finish(now);
+            let started = now
+                - chrono::Duration::from_std(m.average_time)
+                    .unwrap_or_else(|_| chrono::Duration::zero());
+            self.current = Some(MinMaxSession::new(m.mode, started));
+        }
+        if let Some(ref mut session) = self.current {
+            session.last = Some(m.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::NaiveDate;
+
+    use super::{MinMaxTracker, MAX_FINISHED_SESSIONS};
+    use measurement::{Measurement, MinMaxMeasurement};
+    use mode::Mode;
+    use unit::Unit;
+    use value::Value;
+
+    fn measurement(secs: u64) -> Measurement {
+        let mut m = MinMaxMeasurement::new(Mode::VDC_Normal, Value::new(1.0, 3, Unit::VDC, 0));
+        m.max_time = Duration::from_secs(secs / 2);
+        m.average_time = Duration::from_secs(secs);
+        Measurement::MinMax(m)
+    }
+
+    #[test]
+    fn test_sessions() {
+        let t0 = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let at = |secs| t0 + ::chrono::Duration::seconds(secs);
+        let mut tracker = MinMaxTracker::default();
+
+        tracker.start(Some(Mode::VDC_Normal), t0);
+        tracker.update(&measurement(10), at(10));
+        let session = tracker.current().unwrap();
+        assert_eq!(session.started, t0);
+        assert_eq!(session.max_at(), Some(at(5)));
+
+        // reset by DMM button
+        tracker.update(&measurement(2), at(30));
+        assert_eq!(tracker.current().unwrap().started, at(28));
+
+        tracker.finish(at(40));
+        assert!(tracker.current().is_none());
+        let finished = tracker.take_finished();
+        assert_eq!(finished.len(), 2);
+        assert_eq!(finished[0].finished, Some(at(30)));
+        assert_eq!(finished[1].finished, Some(at(40)));
+        assert_eq!(finished[1].duration(), Some(Duration::from_secs(2)));
+
+        for i in 0..(MAX_FINISHED_SESSIONS as i64 + 1) {
+            tracker.start(Some(Mode::VDC_Normal), at(100 + i));
+        }
+        tracker.finish(at(200));
+        let finished = tracker.take_finished();
+        assert_eq!(finished.len(), MAX_FINISHED_SESSIONS);
+        assert_eq!(finished[0].started, at(101));
+    }
+}
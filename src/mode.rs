@@ -1,10 +1,108 @@
 use std::fmt;
 use std::result;
+use std::str::FromStr;
 
 use error::*;
+use unit::Unit;
+use utils::normalize_name;
 
+/// Measurement function (position of the rotary switch and its primary mode).
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Function {
+    VAC,
+    mVAC,
+    VDC,
+    mVDC,
+    TempC,
+    TempF,
+    Resistance,
+    Beeper,
+    Admittance,
+    Diode,
+    Capacitance,
+    Frequency,
+    DutyCycle,
+    PulseWidth,
+    uADC,
+    uAAC,
+    mADC,
+    mAAC,
+    ADC,
+    AAC,
+}
+
+impl Function {
+    /// Returns unit of measured quantity.
+    pub fn unit(self) -> Unit {
+        match self {
+            Function::VAC | Function::mVAC => Unit::VAC,
+            Function::VDC | Function::mVDC | Function::Diode => Unit::VDC,
+            Function::TempC => Unit::Celsius,
+            Function::TempF => Unit::Fahrenheit,
+            Function::Resistance | Function::Beeper => Unit::Ohm,
+            Function::Admittance => Unit::S,
+            Function::Capacitance => Unit::F,
+            Function::Frequency => Unit::Hz,
+            Function::DutyCycle => Unit::Percent,
+            Function::PulseWidth => Unit::s,
+            Function::uADC | Function::mADC | Function::ADC => Unit::ADC,
+            Function::uAAC | Function::mAAC | Function::AAC => Unit::AAC,
+        }
+    }
+
+    /// Is it a voltage measurement function?
+    pub fn is_voltage(self) -> bool {
+        matches!(
+            self,
+            Function::VAC | Function::mVAC | Function::VDC | Function::mVDC
+        )
+    }
+
+    /// Is it a current measurement function?
+    pub fn is_current(self) -> bool {
+        matches!(
+            self,
+            Function::uADC
+                | Function::uAAC
+                | Function::mADC
+                | Function::mAAC
+                | Function::ADC
+                | Function::AAC
+        )
+    }
+
+    /// Is it a temperature measurement function?
+    pub fn is_temperature(self) -> bool {
+        matches!(self, Function::TempC | Function::TempF)
+    }
+}
+
+/// Submode of measurement function.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Submode {
+    Normal,
+    Hz,
+    Peak,
+    LowPass,
+    dBV,
+    dBm,
+    AC_DC,
+    T1_T2,
+    T2_T1,
+    T1_T2_Diff,
+    T2_T1_Diff,
+    Short,
+    Open,
+    Alarm,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Mode {
     VAC_Normal,
     VAC_Normal_Rel,
@@ -106,10 +204,206 @@ pub enum Mode {
     AAC_Peak,
 }
 
+/// All modes in mode word order.
+const MODES: [Mode; 79] = [
+    Mode::VAC_Normal,
+    Mode::VAC_Normal_Rel,
+    Mode::VAC_Hz,
+    Mode::VAC_Peak,
+    Mode::VAC_LowPass,
+    Mode::VAC_LowPass_Rel,
+    Mode::VAC_dBV,
+    Mode::VAC_dBV_Rel,
+    Mode::VAC_dBm,
+    Mode::VAC_dBm_Rel,
+    Mode::mVAC_Normal,
+    Mode::mVAC_Normal_Rel,
+    Mode::mVAC_Hz,
+    Mode::mVAC_Peak,
+    Mode::mVAC_AC_DC,
+    Mode::mVAC_AC_DC_Rel,
+    Mode::VDC_Normal,
+    Mode::VDC_Normal_Rel,
+    Mode::VDC_AC_DC,
+    Mode::VDC_AC_DC_Rel,
+    Mode::VDC_Peak,
+    Mode::mVDC_Normal,
+    Mode::mVDC_Normal_Rel,
+    Mode::mVDC_Peak,
+    Mode::TempC_T1_T2,
+    Mode::TempC_T1_T2_Rel,
+    Mode::TempC_T2_T1,
+    Mode::TempC_T2_T1_Rel,
+    Mode::TempC_T1_T2_Diff,
+    Mode::TempC_T2_T1_Diff,
+    Mode::TempF_T1_T2,
+    Mode::TempF_T1_T2_Rel,
+    Mode::TempF_T2_T1,
+    Mode::TempF_T2_T1_Rel,
+    Mode::TempF_T1_T2_Diff,
+    Mode::TempF_T2_T1_Diff,
+    Mode::Resistance,
+    Mode::Resistance_Rel,
+    Mode::Beeper_Short,
+    Mode::Beeper_Open,
+    Mode::Admittance,
+    Mode::Admittance_Rel,
+    Mode::Diode_Normal,
+    Mode::Diode_Alarm,
+    Mode::Capacitance,
+    Mode::Capacitance_Rel,
+    Mode::Frequency,
+    Mode::Frequency_Rel,
+    Mode::DutyCycle,
+    Mode::DutyCycle_Rel,
+    Mode::PulseWidth,
+    Mode::PulseWidth_Rel,
+    Mode::uADC_Normal,
+    Mode::uADC_Normal_Rel,
+    Mode::uADC_AC_DC,
+    Mode::uADC_AC_DC_Rel,
+    Mode::uADC_Peak,
+    Mode::uAAC_Normal,
+    Mode::uAAC_Normal_Rel,
+    Mode::uAAC_Hz,
+    Mode::uAAC_Peak,
+    Mode::mADC_Normal,
+    Mode::mADC_Normal_Rel,
+    Mode::mADC_AC_DC,
+    Mode::mADC_AC_DC_Rel,
+    Mode::mADC_Peak,
+    Mode::mAAC_Normal,
+    Mode::mAAC_Normal_Rel,
+    Mode::mAAC_Hz,
+    Mode::mAAC_Peak,
+    Mode::ADC_Normal,
+    Mode::ADC_Normal_Rel,
+    Mode::ADC_AC_DC,
+    Mode::ADC_AC_DC_Rel,
+    Mode::ADC_Peak,
+    Mode::AAC_Normal,
+    Mode::AAC_Normal_Rel,
+    Mode::AAC_Hz,
+    Mode::AAC_Peak,
+];
+
 impl Mode {
+    /// Returns an iterator over all modes.
+    pub fn all() -> impl Iterator<Item = Mode> {
+        MODES.iter().cloned()
+    }
+
     pub(crate) fn from_bin(data: &[u8]) -> Result<Mode> {
         use byteorder::{ByteOrder, LittleEndian};
-        let m = LittleEndian::read_u16(data);
+        Mode::from_word(LittleEndian::read_u16(data))
+    }
+
+    /// Returns mode word.
+    fn word(self) -> u16 {
+        let bin = self.to_bin();
+        (u16::from(bin[1]) << 8) | u16::from(bin[0])
+    }
+
+    /// Returns measurement function.
+    pub fn function(self) -> Function {
+        match self.word() >> 8 {
+            0x11 => Function::VAC,
+            0x21 => Function::mVAC,
+            0x31 => Function::VDC,
+            0x41 => Function::mVDC,
+            0x42 => Function::TempC,
+            0x43 => Function::TempF,
+            0x51 => Function::Resistance,
+            0x52 => Function::Beeper,
+            0x53 => Function::Admittance,
+            0x61 => Function::Diode,
+            0x62 => Function::Capacitance,
+            0x71 => Function::Frequency,
+            0x72 => Function::DutyCycle,
+            0x73 => Function::PulseWidth,
+            0x81 => Function::uADC,
+            0x82 => Function::uAAC,
+            0x91 => Function::mADC,
+            0x92 => Function::mAAC,
+            0xA1 => Function::ADC,
+            0xA2 => Function::AAC,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns submode of measurement function.
+    pub fn submode(self) -> Submode {
+        let function = self.function();
+        match (function, self.word() & 0xFF) {
+            (Function::Beeper, 0x11) => Submode::Short,
+            (Function::Beeper, 0x12) => Submode::Open,
+            (Function::Diode, 0x12) => Submode::Alarm,
+            (Function::TempC, sub) | (Function::TempF, sub) => match sub >> 4 {
+                1 => Submode::T1_T2,
+                2 => Submode::T2_T1,
+                3 => Submode::T1_T2_Diff,
+                _ => Submode::T2_T1_Diff,
+            },
+            (_, 0x11) | (_, 0x12) => Submode::Normal,
+            (Function::VAC, sub) => match sub >> 4 {
+                2 => Submode::Hz,
+                3 => Submode::Peak,
+                4 => Submode::LowPass,
+                5 => Submode::dBV,
+                _ => Submode::dBm,
+            },
+            (Function::mVAC, sub) => match sub >> 4 {
+                2 => Submode::Hz,
+                3 => Submode::Peak,
+                _ => Submode::AC_DC,
+            },
+            (Function::mVDC, _) => Submode::Peak,
+            (function, sub) if function.unit() == Unit::AAC => match sub >> 4 {
+                2 => Submode::Hz,
+                _ => Submode::Peak,
+            },
+            (_, sub) => match sub >> 4 {
+                2 => Submode::AC_DC,
+                _ => Submode::Peak,
+            },
+        }
+    }
+
+    /// Is relative mode on?
+    pub fn is_relative(self) -> bool {
+        match self.function() {
+            Function::Beeper | Function::Diode => false,
+            _ => self.word() & 0x0F == 0x02,
+        }
+    }
+
+    /// Returns relative (`relative` is `true`) or absolute counterpart of the mode.
+    ///
+    /// Returns `None` if the submode doesn't support relative measurement.
+    pub fn with_relative(self, relative: bool) -> Option<Mode> {
+        if self.is_relative() == relative {
+            return Some(self);
+        }
+        if let Function::Beeper | Function::Diode = self.function() {
+            return None;
+        }
+        let word = (self.word() & 0xFFF0) | if relative { 0x02 } else { 0x01 };
+        Mode::from_word(word).ok()
+    }
+
+    /// Returns unit of main value.
+    pub fn unit(self) -> Unit {
+        match self.submode() {
+            Submode::Hz => Unit::Hz,
+            Submode::dBV => Unit::dBV,
+            Submode::dBm => Unit::dBm,
+            Submode::AC_DC if self.function().is_voltage() => Unit::VAcDc,
+            Submode::AC_DC => Unit::AAcDc,
+            _ => self.function().unit(),
+        }
+    }
+
+    fn from_word(m: u16) -> Result<Mode> {
         match m {
             0x1111 => Ok(Mode::VAC_Normal),
             0x1112 => Ok(Mode::VAC_Normal_Rel),
@@ -422,3 +716,83 @@ impl fmt::Display for Mode {
         }
     }
 }
+
+impl FromStr for Mode {
+    type Err = Error;
+
+    /// Parses mode from its display name ("mVAC/AC+DC/Rel")
+    /// or variant name ("mVAC_AC_DC_Rel", "madc-ac-dc", "vdc").
+    ///
+    /// Matching is case-insensitive, variant name may omit "Normal" submode,
+    /// '_', '-' and spaces in variant name are ignored.
+    /// Variant names with "T1-T2" or "T2-T1" are rejected,
+    /// since they mean a difference in display names ("Temp C/T1-T2").
+    fn from_str(s: &str) -> Result<Mode> {
+        if let Some(mode) = Mode::all().find(|mode| s.eq_ignore_ascii_case(&mode.to_string())) {
+            return Ok(mode);
+        }
+        let lowercase = s.to_ascii_lowercase();
+        if lowercase.contains("t1-t2") || lowercase.contains("t2-t1") {
+            return Err(ErrorKind::UnknownModeName(s.into()).into());
+        }
+        let name = normalize_name(s);
+        Mode::all()
+            .find(|mode| {
+                let variant = format!("{:?}", mode);
+                name == normalize_name(&variant)
+                    || name == normalize_name(&variant.replace("_Normal", ""))
+            })
+            .ok_or_else(|| ErrorKind::UnknownModeName(s.into()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Function, Mode, Submode};
+    use unit::Unit;
+
+    #[test]
+    fn test_decomposition() {
+        let m = Mode::mADC_AC_DC_Rel;
+        assert_eq!(m.function(), Function::mADC);
+        assert!(m.function().is_current());
+        assert_eq!(m.submode(), Submode::AC_DC);
+        assert!(m.is_relative());
+        assert_eq!(m.unit(), Unit::AAcDc);
+        assert_eq!(m.with_relative(false), Some(Mode::mADC_AC_DC));
+
+        assert_eq!(Mode::TempF_T2_T1_Rel.submode(), Submode::T2_T1);
+        assert_eq!(Mode::TempC_T2_T1_Diff.submode(), Submode::T2_T1_Diff);
+        assert_eq!(Mode::uAAC_Hz.submode(), Submode::Hz);
+        assert_eq!(Mode::uAAC_Hz.unit(), Unit::Hz);
+        assert_eq!(Mode::VAC_dBm_Rel.submode(), Submode::dBm);
+        assert_eq!(Mode::mVDC_Peak.submode(), Submode::Peak);
+        assert_eq!(Mode::Diode_Alarm.submode(), Submode::Alarm);
+        assert!(!Mode::Diode_Alarm.is_relative());
+        assert!(!Mode::Beeper_Open.is_relative());
+    }
+
+    #[test]
+    fn test_from_str() {
+        for mode in Mode::all() {
+            assert_eq!(mode.to_string().parse::<Mode>().unwrap(), mode);
+            assert_eq!(format!("{:?}", mode).parse::<Mode>().unwrap(), mode);
+        }
+        assert_eq!("vdc".parse::<Mode>().unwrap(), Mode::VDC_Normal);
+        assert_eq!("temp c/t1-t2".parse::<Mode>().unwrap(), Mode::TempC_T1_T2_Diff);
+        assert!("temp-c-t1-t2".parse::<Mode>().is_err());
+        assert!("temp-c-t1-t2-diff".parse::<Mode>().is_err());
+        assert_eq!("temp_c_t1_t2".parse::<Mode>().unwrap(), Mode::TempC_T1_T2);
+        assert_eq!("madc-ac-dc-rel".parse::<Mode>().unwrap(), Mode::mADC_AC_DC_Rel);
+        assert!("vdc/hz".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn test_with_relative() {
+        assert_eq!(Mode::VAC_LowPass.with_relative(true), Some(Mode::VAC_LowPass_Rel));
+        assert_eq!(Mode::TempC_T2_T1.with_relative(true), Some(Mode::TempC_T2_T1_Rel));
+        assert_eq!(Mode::VDC_Peak.with_relative(true), None);
+        assert_eq!(Mode::Diode_Normal.with_relative(true), None);
+        assert_eq!(Mode::Diode_Normal.with_relative(false), Some(Mode::Diode_Normal));
+    }
+}
@@ -0,0 +1,150 @@
+use uom::si::capacitance::farad;
+use uom::si::electric_current::ampere;
+use uom::si::electric_potential::volt;
+use uom::si::electrical_conductance::siemens;
+use uom::si::electrical_resistance::ohm;
+use uom::si::f64::{Capacitance, ElectricCurrent, ElectricPotential, ElectricalConductance,
+                   ElectricalResistance, Frequency, TemperatureInterval, ThermodynamicTemperature,
+                   Time};
+use uom::si::frequency::hertz;
+use uom::si::temperature_interval;
+use uom::si::thermodynamic_temperature::{degree_celsius, degree_fahrenheit};
+use uom::si::time::second;
+
+use error::*;
+use measurement::Measurement;
+use mode::Submode;
+use unit::Unit;
+use value::Value;
+
+/// Measured value as `uom` quantity.
+#[derive(Clone, Copy, Debug)]
+pub enum Quantity {
+    /// Volts (DC, AC or AC+DC).
+    ElectricPotential(ElectricPotential),
+
+    /// Ampers (DC, AC or AC+DC).
+    ElectricCurrent(ElectricCurrent),
+
+    /// Ohms.
+    ElectricalResistance(ElectricalResistance),
+
+    /// Farads.
+    Capacitance(Capacitance),
+
+    /// Hertz.
+    Frequency(Frequency),
+
+    /// Siemens.
+    ElectricalConductance(ElectricalConductance),
+
+    /// Celsius or Fahrenheit degrees (absolute temperature).
+    ThermodynamicTemperature(ThermodynamicTemperature),
+
+    /// Celsius or Fahrenheit degrees (temperature difference).
+    TemperatureInterval(TemperatureInterval),
+
+    /// Seconds.
+    Time(Time),
+}
+
+impl Value {
+    /// Converts the value to `uom` quantity corresponding to the unit.
+    ///
+    /// Temperatures are converted to `ThermodynamicTemperature`,
+    /// use `to_interval_quantity` for differences (T1-T2, relative values).
+    /// Returns `ErrorKind::NoQuantity` for dBm, dBV and percents.
+    /// Overloaded values are converted to infinities (see `Value::si`).
+    pub fn to_quantity(&self) -> Result<Quantity> {
+        self.quantity(false)
+    }
+
+    /// Converts the value to `uom` quantity treating temperatures as differences
+    /// (`TemperatureInterval`, 1.5 °C is 1.5 K).
+    ///
+    /// Other units are converted as by `to_quantity`.
+    pub fn to_interval_quantity(&self) -> Result<Quantity> {
+        self.quantity(true)
+    }
+
+    fn quantity(&self, is_interval: bool) -> Result<Quantity> {
+        let v = self.si();
+        let quantity = match self.unit.unit {
+            Unit::VDC | Unit::VAC | Unit::VAcDc => {
+                Quantity::ElectricPotential(ElectricPotential::new::<volt>(v))
+            }
+            Unit::ADC | Unit::AAC | Unit::AAcDc => {
+                Quantity::ElectricCurrent(ElectricCurrent::new::<ampere>(v))
+            }
+            Unit::Ohm => Quantity::ElectricalResistance(ElectricalResistance::new::<ohm>(v)),
+            Unit::F => Quantity::Capacitance(Capacitance::new::<farad>(v)),
+            Unit::Hz => Quantity::Frequency(Frequency::new::<hertz>(v)),
+            Unit::S => Quantity::ElectricalConductance(ElectricalConductance::new::<siemens>(v)),
+            Unit::Celsius if is_interval => Quantity::TemperatureInterval(
+                TemperatureInterval::new::<temperature_interval::degree_celsius>(v),
+            ),
+            Unit::Fahrenheit if is_interval => Quantity::TemperatureInterval(
+                TemperatureInterval::new::<temperature_interval::degree_fahrenheit>(v),
+            ),
+            Unit::Celsius => Quantity::ThermodynamicTemperature(
+                ThermodynamicTemperature::new::<degree_celsius>(v),
+            ),
+            Unit::Fahrenheit => Quantity::ThermodynamicTemperature(
+                ThermodynamicTemperature::new::<degree_fahrenheit>(v),
+            ),
+            Unit::s => Quantity::Time(Time::new::<second>(v)),
+            Unit::Percent | Unit::dBm | Unit::dBV => {
+                return Err(ErrorKind::NoQuantity(self.unit.unit).into())
+            }
+        };
+        Ok(quantity)
+    }
+}
+
+impl Measurement {
+    /// Converts primary value (see `Measurement::primary`) to `uom` quantity.
+    ///
+    /// Temperature differences (T1-T2 and T2-T1 modes, relative values)
+    /// are converted to `TemperatureInterval`.
+    pub fn primary_quantity(&self) -> Result<Quantity> {
+        let is_difference = match *self {
+            Measurement::Relative(_) => true,
+            ref m => matches!(m.mode().submode(), Submode::T1_T2_Diff | Submode::T2_T1_Diff),
+        };
+        if is_difference {
+            self.primary().to_interval_quantity()
+        } else {
+            self.primary().to_quantity()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::electric_potential::volt;
+    use uom::si::temperature_interval::kelvin;
+    use uom::si::thermodynamic_temperature::degree_celsius;
+
+    use super::Quantity;
+    use unit::Unit;
+    use value::Value;
+
+    #[test]
+    fn test_to_quantity() {
+        match Value::new(12.5, 1, Unit::VDC, -3).to_quantity().unwrap() {
+            Quantity::ElectricPotential(v) => assert!((v.get::<volt>() - 0.0125).abs() < 1e-9),
+            q => panic!("unexpected quantity: {:?}", q),
+        }
+        match Value::new(212.0, 1, Unit::Fahrenheit, 0).to_quantity().unwrap() {
+            Quantity::ThermodynamicTemperature(t) => {
+                assert!((t.get::<degree_celsius>() - 100.0).abs() < 1e-6)
+            }
+            q => panic!("unexpected quantity: {:?}", q),
+        }
+        match Value::new(1.5, 1, Unit::Celsius, 0).to_interval_quantity().unwrap() {
+            Quantity::TemperatureInterval(t) => assert!((t.get::<kelvin>() - 1.5).abs() < 1e-6),
+            q => panic!("unexpected quantity: {:?}", q),
+        }
+        assert!(Value::new(-3.0, 1, Unit::dBV, 0).to_quantity().is_err());
+    }
+}
@@ -1,7 +1,17 @@
+use std::fmt;
+use std::str::FromStr;
+
 use error::*;
+use mode::{Function, Mode, Submode};
+use unit::{Unit, UnitExp};
+use utils::normalize_name;
+use value::Value;
 
 /// Measuring range.
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+///
+/// A meaning of the step depends on mode, see `Range::full_scale`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Range {
     /// Auto range
     Auto,
@@ -12,7 +22,7 @@ pub enum Range {
     /// 600 mV/60 V/6000 uA/600 mA/6 KOhm/600 Hz/60 nF
     Step2,
 
-    /// 600V/60 KOhm/6 KHz/600 nF
+    /// 600 V/60 KOhm/6 KHz/600 nF
     Step3,
 
     /// 1000 V/600 KOhm/60 KHz/6 uF
@@ -61,3 +71,226 @@ impl Range {
         }
     }
 }
+
+/// All ranges in ascending order.
+const RANGES: [Range; 9] = [
+    Range::Auto,
+    Range::Step1,
+    Range::Step2,
+    Range::Step3,
+    Range::Step4,
+    Range::Step5,
+    Range::Step6,
+    Range::Step7,
+    Range::Step8,
+];
+
+/// Full scale values (value, exponent) of `Range::Step1`, `Range::Step2`, etc.
+const VOLTS: &[(f32, isize)] = &[(6.0, 0), (60.0, 0), (600.0, 0), (1000.0, 0)];
+const MILLIVOLTS: &[(f32, isize)] = &[(60.0, -3), (600.0, -3)];
+const MICROAMPERES: &[(f32, isize)] = &[(600.0, -6), (6000.0, -6)];
+const MILLIAMPERES: &[(f32, isize)] = &[(60.0, -3), (600.0, -3)];
+const AMPERES: &[(f32, isize)] = &[(20.0, 0)];
+const OHMS: &[(f32, isize)] = &[
+    (600.0, 0),
+    (6.0, 3),
+    (60.0, 3),
+    (600.0, 3),
+    (6.0, 6),
+    (60.0, 6),
+];
+const HERTZ: &[(f32, isize)] = &[
+    (60.0, 0),
+    (600.0, 0),
+    (6.0, 3),
+    (60.0, 3),
+    (600.0, 3),
+    (6.0, 6),
+    (60.0, 6),
+];
+const FARADS: &[(f32, isize)] = &[
+    (6.0, -9),
+    (60.0, -9),
+    (600.0, -9),
+    (6.0, -6),
+    (60.0, -6),
+    (600.0, -6),
+    (6.0, -3),
+    (60.0, -3),
+];
+
+/// Returns unit and full scale values of manual ranges in `mode`.
+fn scales(mode: Mode) -> Option<(Unit, &'static [(f32, isize)])> {
+    let scales = match mode.function() {
+        Function::VAC | Function::VDC => VOLTS,
+        Function::mVAC | Function::mVDC => MILLIVOLTS,
+        Function::Resistance => OHMS,
+        Function::Capacitance => FARADS,
+        Function::Frequency => HERTZ,
+        Function::uADC | Function::uAAC => MICROAMPERES,
+        Function::mADC | Function::mAAC => MILLIAMPERES,
+        Function::ADC | Function::AAC => AMPERES,
+        _ => return None,
+    };
+    // Ranges select input voltage or current even if main value is frequency or decibels.
+    let unit = match mode.submode() {
+        Submode::Hz | Submode::dBV | Submode::dBm => mode.function().unit(),
+        _ => mode.unit(),
+    };
+    Some((unit, scales))
+}
+
+/// Is `a` the same physical quantity as `b`?
+fn is_same_quantity(a: Unit, b: Unit) -> bool {
+    fn quantity(unit: Unit) -> Unit {
+        match unit {
+            Unit::VAC | Unit::VAcDc => Unit::VDC,
+            Unit::AAC | Unit::AAcDc => Unit::ADC,
+            unit => unit,
+        }
+    }
+    quantity(a) == quantity(b)
+}
+
+impl Range {
+    /// Returns ranges supported in `mode`.
+    ///
+    /// `Range::Auto` is supported in any mode,
+    /// modes without manual range selection support `Range::Auto` only.
+    pub fn supported(mode: Mode) -> &'static [Range] {
+        match scales(mode) {
+            Some((_, scales)) => &RANGES[..=scales.len()],
+            None => &RANGES[..1],
+        }
+    }
+
+    /// Is the range supported in `mode`?
+    pub fn is_supported(self, mode: Mode) -> bool {
+        Range::supported(mode).contains(&self)
+    }
+
+    /// Returns full scale value of the range in `mode`.
+    ///
+    /// Returns `None` for `Range::Auto` and unsupported ranges.
+    pub fn full_scale(self, mode: Mode) -> Option<Value> {
+        let (unit, scales) = scales(mode)?;
+        let i = usize::from(self.to_bin());
+        if i == 0 || i > scales.len() {
+            return None;
+        }
+        let (value, exponent) = scales[i - 1];
+        Some(Value {
+            overload_neg: false,
+            overload_pos: false,
+            value,
+            precision: Some(0),
+            unit: UnitExp { unit, exponent },
+        })
+    }
+
+    /// Returns the range with `full_scale` value in `mode`.
+    ///
+    /// DC, AC and AC+DC units are interchangeable (60 VDC matches 60 VAC range).
+    pub fn from_full_scale(mode: Mode, full_scale: &Value) -> Option<Range> {
+        let (unit, scales) = scales(mode)?;
+        if !is_same_quantity(unit, full_scale.unit.unit) {
+            return None;
+        }
+        let requested = f64::from(full_scale.value) * 10f64.powi(full_scale.unit.exponent as i32);
+        scales
+            .iter()
+            .position(|&(value, exponent)| {
+                let scale = f64::from(value) * 10f64.powi(exponent as i32);
+                (scale - requested).abs() <= scale * 1e-6
+            })
+            .map(|i| RANGES[i + 1])
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Range::Auto => f.write_str("Auto"),
+            step => write!(f, "Step{}", step.to_bin()),
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = Error;
+
+    /// Parses range from its name ("Auto", "Step1", "step 2") or step number ("3").
+    ///
+    /// Matching is case-insensitive.
+    fn from_str(s: &str) -> Result<Range> {
+        let name = normalize_name(s);
+        if name == "auto" {
+            return Ok(Range::Auto);
+        }
+        match name.trim_start_matches("step").parse::<u8>() {
+            Ok(b) if (1..=8).contains(&b) => Range::from_bin(&[b]),
+            _ => Err(ErrorKind::UnknownRangeName(s.into()).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Range;
+    use mode::Mode;
+    use unit::Unit;
+    use value::Value;
+
+    #[test]
+    fn test_supported() {
+        assert_eq!(
+            Range::supported(Mode::mVDC_Normal),
+            &[Range::Auto, Range::Step1, Range::Step2]
+        );
+        assert_eq!(Range::supported(Mode::Capacitance).len(), 9);
+        assert_eq!(Range::supported(Mode::TempC_T1_T2), &[Range::Auto]);
+        assert!(!Range::Step8.is_supported(Mode::mVDC_Normal));
+    }
+
+    #[test]
+    fn test_from_str() {
+        for &range in &super::RANGES {
+            assert_eq!(range.to_string().parse::<Range>().unwrap(), range);
+        }
+        assert_eq!("auto".parse::<Range>().unwrap(), Range::Auto);
+        assert_eq!("step 3".parse::<Range>().unwrap(), Range::Step3);
+        assert_eq!("8".parse::<Range>().unwrap(), Range::Step8);
+        assert!("0".parse::<Range>().is_err());
+        assert!("step9".parse::<Range>().is_err());
+    }
+
+    #[test]
+    fn test_full_scale() {
+        let v = Range::Step2.full_scale(Mode::mVAC_Normal).unwrap();
+        assert_eq!(v.value, 600.0);
+        assert_eq!(v.unit.unit, Unit::VAC);
+        assert_eq!(v.unit.exponent, -3);
+        assert!(Range::Auto.full_scale(Mode::VDC_Normal).is_none());
+        assert!(Range::Step5.full_scale(Mode::VDC_Normal).is_none());
+    }
+
+    #[test]
+    fn test_from_full_scale() {
+        assert_eq!(
+            Range::from_full_scale(Mode::VDC_Normal, &Value::new(60.0, 0, Unit::VDC, 0)),
+            Some(Range::Step2)
+        );
+        assert_eq!(
+            Range::from_full_scale(Mode::Resistance, &Value::new(0.6, 0, Unit::Ohm, 6)),
+            Some(Range::Step4)
+        );
+        assert_eq!(
+            Range::from_full_scale(Mode::VDC_Normal, &Value::new(60.0, 0, Unit::ADC, 0)),
+            None
+        );
+        assert_eq!(
+            Range::from_full_scale(Mode::VDC_Normal, &Value::new(50.0, 0, Unit::VDC, 0)),
+            None
+        );
+    }
+}
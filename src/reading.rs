@@ -0,0 +1,186 @@
+use measurement::{Measurement, NormalMeasurement};
+use mode::Submode;
+use unit::Unit;
+use value::Value;
+
+/// AC voltage or current together with its frequency (`*/Hz` modes).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AcWithFrequency {
+    /// AC voltage or current.
+    pub ac: Value,
+
+    /// Frequency.
+    pub frequency: Value,
+}
+
+/// Decibel level together with AC voltage (`VAC/dBV`, `VAC/dBm` modes).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecibelReading {
+    /// Level in dBV or dBm.
+    pub level: Value,
+
+    /// AC voltage.
+    pub ac: Option<Value>,
+}
+
+/// Two channel temperature reading (`TempC/*`, `TempF/*` modes).
+///
+/// Fields which DMM doesn't send in current mode are `None`.
+/// In difference submodes only `difference` is set: it isn't known which
+/// probe the aux values belong to (they are still available in the measurement).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TemperatureReading {
+    /// T1 probe temperature.
+    pub t1: Option<Value>,
+
+    /// T2 probe temperature.
+    pub t2: Option<Value>,
+
+    /// Difference (T1-T2 or T2-T1 depending on mode).
+    pub difference: Option<Value>,
+}
+
+/// Main and aux values of a normal measurement interpreted according to its mode.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum Reading {
+    /// Main value only.
+    Single(Value),
+    AcWithFrequency(AcWithFrequency),
+    Decibel(DecibelReading),
+    Temperature(TemperatureReading),
+
+    /// Values of a mode without specific interpretation (AC+DC, etc).
+    Other {
+        main: Value,
+        aux1: Option<Value>,
+        aux2: Option<Value>,
+    },
+}
+
+/// Splits `main` and `aux` into the value with `unit` and the other one.
+fn split_by_unit(
+    main: &Value,
+    aux: &Option<Value>,
+    unit: Unit,
+) -> Option<(Value, Option<Value>)> {
+    if main.unit.unit == unit {
+        Some((main.clone(), aux.clone()))
+    } else {
+        match *aux {
+            Some(ref aux) if aux.unit.unit == unit => Some((aux.clone(), Some(main.clone()))),
+            _ => None,
+        }
+    }
+}
+
+impl NormalMeasurement {
+    /// Interprets main and aux values according to the mode.
+    ///
+    /// Value order of each mode is inferred as described in Protocol.md ("Normal measurement").
+    pub fn reading(&self) -> Reading {
+        let submode = self.mode.submode();
+        let reading = match submode {
+            _ if self.mode.function().is_temperature() => {
+                let (t1, t2, difference) = match submode {
+                    Submode::T1_T2 => (Some(&self.main), self.aux1.as_ref(), None),
+                    Submode::T2_T1 => (self.aux1.as_ref(), Some(&self.main), None),
+                    Submode::T1_T2_Diff | Submode::T2_T1_Diff => (None, None, Some(&self.main)),
+                    _ => (Some(&self.main), None, None),
+                };
+                Some(Reading::Temperature(TemperatureReading {
+                    t1: t1.cloned(),
+                    t2: t2.cloned(),
+                    difference: difference.cloned(),
+                }))
+            }
+            Submode::Hz => match split_by_unit(&self.main, &self.aux1, Unit::Hz) {
+                Some((frequency, Some(ac))) => {
+                    Some(Reading::AcWithFrequency(AcWithFrequency { ac, frequency }))
+                }
+                _ => None,
+            },
+            Submode::dBV | Submode::dBm => {
+                let unit = if submode == Submode::dBV {
+                    Unit::dBV
+                } else {
+                    Unit::dBm
+                };
+                split_by_unit(&self.main, &self.aux1, unit)
+                    .map(|(level, ac)| Reading::Decibel(DecibelReading { level, ac }))
+            }
+            _ => None,
+        };
+        reading.unwrap_or_else(|| {
+            if self.aux1.is_none() && self.aux2.is_none() {
+                Reading::Single(self.main.clone())
+            } else {
+                Reading::Other {
+                    main: self.main.clone(),
+                    aux1: self.aux1.clone(),
+                    aux2: self.aux2.clone(),
+                }
+            }
+        })
+    }
+}
+
+impl Measurement {
+    /// Interprets values of a normal measurement according to the mode.
+    ///
+    /// See `NormalMeasurement::reading`.
+    pub fn reading(&self) -> Option<Reading> {
+        match *self {
+            Measurement::Normal(ref m) => Some(m.reading()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reading;
+    use measurement::NormalMeasurement;
+    use mode::Mode;
+    use unit::Unit;
+    use value::Value;
+
+    #[test]
+    fn test_reading() {
+        let m = NormalMeasurement::new(
+            Mode::VAC_Hz,
+            Value::new(50.0, 2, Unit::Hz, 0),
+            Some(Value::new(230.0, 2, Unit::VAC, 0)),
+            None,
+        );
+        match m.reading() {
+            Reading::AcWithFrequency(r) => {
+                assert_eq!(r.ac.value, 230.0);
+                assert_eq!(r.frequency.value, 50.0);
+            }
+            r => panic!("unexpected reading: {:?}", r),
+        }
+
+        let m = NormalMeasurement::new(
+            Mode::TempC_T2_T1_Diff,
+            Value::new(1.5, 2, Unit::Celsius, 0),
+            Some(Value::new(21.5, 2, Unit::Celsius, 0)),
+            Some(Value::new(20.0, 2, Unit::Celsius, 0)),
+        );
+        match m.reading() {
+            Reading::Temperature(r) => {
+                assert!(r.t1.is_none() && r.t2.is_none());
+                assert_eq!(r.difference.unwrap().value, 1.5);
+            }
+            r => panic!("unexpected reading: {:?}", r),
+        }
+
+        let main = Value::new(1.0, 2, Unit::VDC, 0);
+        let m = NormalMeasurement::new(Mode::VDC_Normal, main, None, None);
+        assert!(matches!(m.reading(), Reading::Single(_)));
+    }
+}
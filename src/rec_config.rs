@@ -0,0 +1,226 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{self, NaiveDateTime};
+
+use error::*;
+use utils;
+
+/// Maximum record name length.
+const RECORD_NAME_LENGTH: usize = 10;
+
+/// Record name (up to 10 printable ASCII characters).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String"))]
+pub struct RecordName(String);
+
+impl RecordName {
+    /// Validates `name`.
+    pub fn new(name: &str) -> Result<RecordName> {
+        if !name.chars().all(utils::allowed_char) {
+            return Err(ErrorKind::InvalidRecordName(name.into()).into());
+        }
+        if name.len() > RECORD_NAME_LENGTH {
+            return Err(ErrorKind::RecordNameTooLong(name.into()).into());
+        }
+        Ok(RecordName(name.into()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for RecordName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<RecordName> {
+        RecordName::new(s)
+    }
+}
+
+impl TryFrom<String> for RecordName {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<RecordName> {
+        RecordName::new(&s)
+    }
+}
+
+impl fmt::Display for RecordName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Validated recording parameters.
+///
+/// Deserialized parameters are validated by `RecordConfig::new`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawRecordConfig"))]
+pub struct RecordConfig {
+    name: RecordName,
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
+    interval: Duration,
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
+    duration: Duration,
+}
+
+/// Recording parameters before validation.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RawRecordConfig {
+    name: RecordName,
+    #[serde(with = "::utils::duration_secs")]
+    interval: Duration,
+    #[serde(with = "::utils::duration_secs")]
+    duration: Duration,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawRecordConfig> for RecordConfig {
+    type Error = Error;
+
+    fn try_from(raw: RawRecordConfig) -> Result<RecordConfig> {
+        RecordConfig::new(raw.name, raw.interval, raw.duration)
+    }
+}
+
+impl RecordConfig {
+    /// Creates recording parameters.
+    ///
+    /// `interval` is truncated to whole seconds (1..3600 second(s)),
+    /// `duration` is rounded up to whole minutes (1..143999 minute(s)).
+    pub fn new(name: RecordName, interval: Duration, duration: Duration) -> Result<RecordConfig> {
+        let interval_secs = interval.as_secs();
+        if !(1..=3600).contains(&interval_secs) {
+            let interval_secs = interval_secs.min(u64::from(u16::MAX)) as u16;
+            return Err(ErrorKind::RecordIntervalIsOutOfRange(interval_secs).into());
+        }
+        let minutes = match duration
+            .as_secs()
+            .checked_add(59 + u64::from(duration.subsec_nanos() > 0))
+        {
+            Some(secs) => secs / 60,
+            None => return Err(ErrorKind::RecordDurationIsOutOfRange(u32::MAX).into()),
+        };
+        if !(1..=143_999).contains(&minutes) {
+            let minutes = minutes.min(u64::from(u32::MAX)) as u32;
+            return Err(ErrorKind::RecordDurationIsOutOfRange(minutes).into());
+        }
+        Ok(RecordConfig {
+            name,
+            interval: Duration::from_secs(interval_secs),
+            duration: Duration::from_secs(minutes * 60),
+        })
+    }
+
+    pub fn name(&self) -> &RecordName {
+        &self.name
+    }
+
+    /// Sampling interval (whole seconds).
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Recording duration (whole minutes).
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Expected number of samples.
+    pub fn sample_count(&self) -> u64 {
+        self.duration.as_secs() / self.interval.as_secs()
+    }
+
+    /// Planned end time of recording started at `start`.
+    pub fn end_time(&self, start: NaiveDateTime) -> NaiveDateTime {
+        start + chrono::Duration::seconds(self.duration.as_secs() as i64)
+    }
+
+    /// Would the record need more than `free_samples` samples of DMM memory?
+    ///
+    /// DMM stops recording when memory is full. Its capacity isn't documented,
+    /// so the number of samples still free is up to the caller.
+    pub fn exceeds_free_memory(&self, free_samples: u64) -> bool {
+        self.sample_count() > free_samples
+    }
+
+    /// Interval in seconds as sent to DMM.
+    pub(crate) fn interval_secs(&self) -> u16 {
+        self.interval.as_secs() as u16
+    }
+
+    /// Duration in minutes as sent to DMM.
+    pub(crate) fn duration_minutes(&self) -> u32 {
+        (self.duration.as_secs() / 60) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{RecordConfig, RecordName};
+    use error::ErrorKind;
+
+    #[test]
+    fn test_record_config() {
+        assert!(matches!(
+            *RecordName::new("01234567890").unwrap_err().kind(),
+            ErrorKind::RecordNameTooLong(_)
+        ));
+        assert!(matches!(
+            *RecordName::new("naïve").unwrap_err().kind(),
+            ErrorKind::InvalidRecordName(_)
+        ));
+
+        let name: RecordName = "test".parse().unwrap();
+        let config = RecordConfig::new(
+            name.clone(),
+            Duration::from_millis(2500),
+            Duration::from_secs(90),
+        )
+        .unwrap();
+        assert_eq!(config.interval(), Duration::from_secs(2));
+        assert_eq!(config.duration_minutes(), 2);
+        assert_eq!(config.sample_count(), 60);
+        assert!(!config.exceeds_free_memory(60));
+        assert!(config.exceeds_free_memory(59));
+
+        let config = RecordConfig::new(
+            name.clone(),
+            Duration::from_secs(1),
+            Duration::from_secs(24 * 3600),
+        )
+        .unwrap();
+        assert!(config.exceeds_free_memory(20_000));
+
+        let interval = Duration::from_secs(0);
+        assert!(RecordConfig::new(name.clone(), interval, Duration::from_secs(60)).is_err());
+        assert!(matches!(
+            *RecordConfig::new(name, Duration::from_secs(1), Duration::MAX)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::RecordDurationIsOutOfRange(_)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        let config: RecordConfig =
+            ::serde_json::from_str(r#"{"name": "test", "interval": 2, "duration": 90}"#).unwrap();
+        assert_eq!(config.duration_minutes(), 2);
+        assert!(::serde_json::from_str::<RecordConfig>(
+            r#"{"name": "test", "interval": 0, "duration": 60}"#
+        )
+        .is_err());
+        assert!(::serde_json::from_str::<RecordName>(r#""01234567890""#).is_err());
+    }
+}
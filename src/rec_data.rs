@@ -3,7 +3,30 @@ use chrono::NaiveDateTime;
 use value::Value;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RecordDataItem {
     pub value: Value,
     pub timestamp: NaiveDateTime,
 }
+
+/// Progress of record download.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordProgress {
+    /// A number of samples received (including skipped by start offset).
+    pub received: u32,
+
+    /// Sample count of the record.
+    pub total: u32,
+}
+
+impl RecordProgress {
+    /// Returns downloaded part (0.0..1.0).
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (f64::from(self.received) / f64::from(self.total)).min(1.0)
+        }
+    }
+}
@@ -9,6 +9,7 @@ use unit::UnitExp;
 use utils::{read_stringz, read_datetime};
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RecordInfo {
     /// Name of the record.
     pub name: String,
@@ -17,9 +18,11 @@ pub struct RecordInfo {
     pub unit: UnitExp,
 
     /// Interval.
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
     pub interval: Duration,
 
-    /// Duration.
+    /// Duration (DMM reports it in seconds although `RecordConfig` sends minutes).
+    #[cfg_attr(feature = "serde", serde(with = "::utils::duration_secs"))]
     pub duration: Duration,
 
     /// Sample count.
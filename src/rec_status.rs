@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+
+use rec_config::RecordConfig;
+use rec_info::RecordInfo;
+
+/// Recording progress.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordStatus {
+    /// Is recording in progress (according to measurement flags)?
+    pub is_recording: bool,
+
+    /// Info of the newest record (it's the record in progress while recording).
+    pub info: Option<RecordInfo>,
+
+    /// Host date/time when the status has been read.
+    pub time: NaiveDateTime,
+
+    /// Config the newest record has been started with by this host (if it's known).
+    pub config: Option<RecordConfig>,
+}
+
+impl RecordStatus {
+    /// A number of samples recorded so far.
+    pub fn samples(&self) -> u32 {
+        self.info.as_ref().map_or(0, |info| info.sample_count)
+    }
+
+    /// Recording time so far.
+    ///
+    /// While recording it's counted from the record start by host clock,
+    /// otherwise it's the duration reported by DMM.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.info.as_ref().map(|info| {
+            if self.is_recording {
+                (self.time - info.start)
+                    .to_std()
+                    .unwrap_or_else(|_| Duration::from_secs(0))
+            } else {
+                info.duration
+            }
+        })
+    }
+
+    /// Time left until the recording finishes (zero if it's not in progress).
+    ///
+    /// It's `None` while recording if the record config isn't known.
+    pub fn remaining(&self) -> Option<Duration> {
+        if !self.is_recording {
+            return Some(Duration::from_secs(0));
+        }
+        match (self.info.as_ref(), self.config.as_ref()) {
+            (Some(info), Some(config)) => Some(
+                (config.end_time(info.start) - self.time)
+                    .to_std()
+                    .unwrap_or_else(|_| Duration::from_secs(0)),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{self, NaiveDate};
+
+    use super::RecordStatus;
+    use rec_config::{RecordConfig, RecordName};
+    use rec_info::RecordInfo;
+    use unit::{Unit, UnitExp};
+    use value::Value;
+
+    fn status(is_recording: bool, elapsed: i64, with_config: bool) -> RecordStatus {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let unit = UnitExp {
+            unit: Unit::VDC,
+            exponent: 0,
+        };
+        let value = Value::new(1.0, 4, unit.unit, unit.exponent);
+        let name: RecordName = "test".parse().unwrap();
+        let config = RecordConfig::new(
+            name.clone(),
+            Duration::from_secs(10),
+            Duration::from_secs(600),
+        )
+        .unwrap();
+        RecordStatus {
+            is_recording,
+            info: Some(RecordInfo {
+                name: name.as_str().into(),
+                unit,
+                interval: Duration::from_secs(10),
+                duration: Duration::from_secs(480),
+                sample_count: 12,
+                max: value.clone(),
+                average: value.clone(),
+                min: value,
+                start,
+            }),
+            time: start + chrono::Duration::seconds(elapsed),
+            config: if with_config { Some(config) } else { None },
+        }
+    }
+
+    #[test]
+    fn test_elapsed() {
+        assert_eq!(status(true, 125, true).elapsed(), Some(Duration::from_secs(125)));
+        // host clock behind DMM clock
+        assert_eq!(status(true, -5, true).elapsed(), Some(Duration::from_secs(0)));
+        assert_eq!(status(false, 700, true).elapsed(), Some(Duration::from_secs(480)));
+        let mut s = status(true, 125, true);
+        s.info = None;
+        assert_eq!(s.elapsed(), None);
+    }
+
+    #[test]
+    fn test_remaining() {
+        assert_eq!(status(true, 125, true).remaining(), Some(Duration::from_secs(475)));
+        assert_eq!(status(true, 700, true).remaining(), Some(Duration::from_secs(0)));
+        assert_eq!(status(true, 125, false).remaining(), None);
+        assert_eq!(status(false, 125, false).remaining(), Some(Duration::from_secs(0)));
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt;
+
+use rec_data::RecordDataItem;
+use rec_info::RecordInfo;
+use value::Value;
+
+/// Statistic of record samples reported by DMM.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecordStatistic {
+    Max,
+    Average,
+    Min,
+}
+
+impl fmt::Display for RecordStatistic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            RecordStatistic::Max => "max",
+            RecordStatistic::Average => "average",
+            RecordStatistic::Min => "min",
+        })
+    }
+}
+
+/// Problem found in downloaded record samples.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum RecordIssue {
+    /// Sample count differs from `RecordInfo::sample_count`.
+    CountMismatch { expected: u32, actual: usize },
+
+    /// `missing` samples are absent before sample `index`.
+    Gap { index: usize, missing: u64 },
+
+    /// Sample `index` has the same timestamp as previous one.
+    Duplicate { index: usize },
+
+    /// Sample `index` is `delta` seconds after previous one
+    /// (less than the interval or backwards).
+    Irregular { index: usize, delta: i64 },
+
+    /// Statistic computed from samples differs from reported by DMM.
+    StatisticMismatch {
+        name: RecordStatistic,
+        reported: Value,
+        computed: f64,
+    },
+}
+
+/// Result of record samples verification.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordReport {
+    pub issues: Vec<RecordIssue>,
+}
+
+impl RecordReport {
+    /// Are samples complete and consistent with record info?
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Returns allowed difference of a value rounded by DMM.
+fn tolerance(value: &Value) -> f64 {
+    match value.precision {
+        Some(precision) => 10f64.powi(-(precision as i32)),
+        None => f64::from(value.value.abs()) * f64::from(f32::EPSILON),
+    }
+}
+
+impl RecordInfo {
+    /// Verifies downloaded samples against the record info.
+    ///
+    /// Checks sample count, timestamps advancing by `interval`
+    /// and max/average/min values (within precision, overloaded samples are ignored).
+    pub fn verify(&self, items: &[RecordDataItem]) -> RecordReport {
+        let mut report = RecordReport::default();
+        if items.len() != self.sample_count as usize {
+            report.issues.push(RecordIssue::CountMismatch {
+                expected: self.sample_count,
+                actual: items.len(),
+            });
+        }
+
+        let interval = self.interval.as_secs() as i64;
+        for (index, pair) in items.windows(2).enumerate().map(|(i, pair)| (i + 1, pair)) {
+            let delta = (pair[1].timestamp - pair[0].timestamp).num_seconds();
+            if delta == interval {
+                continue;
+            }
+            report.issues.push(if delta == 0 {
+                RecordIssue::Duplicate { index }
+            } else if interval > 0 && delta > interval && delta % interval == 0 {
+                RecordIssue::Gap {
+                    index,
+                    missing: (delta / interval - 1) as u64,
+                }
+            } else {
+                RecordIssue::Irregular { index, delta }
+            });
+        }
+
+        let values: Vec<f64> = items
+            .iter()
+            .filter(|item| !item.value.overload_neg && !item.value.overload_pos)
+            .map(|item| f64::from(item.value.value))
+            .collect();
+        if !values.is_empty() {
+            let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let average = values.iter().sum::<f64>() / values.len() as f64;
+            for &(name, reported, computed) in &[
+                (RecordStatistic::Max, &self.max, max),
+                (RecordStatistic::Average, &self.average, average),
+                (RecordStatistic::Min, &self.min, min),
+            ] {
+                if reported.overload_neg || reported.overload_pos {
+                    continue;
+                }
+                if (f64::from(reported.value) - computed).abs() > tolerance(reported) {
+                    report.issues.push(RecordIssue::StatisticMismatch {
+                        name,
+                        reported: reported.clone(),
+                        computed,
+                    });
+                }
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{self, NaiveDate};
+
+    use super::{RecordIssue, RecordStatistic};
+    use rec_data::RecordDataItem;
+    use rec_info::RecordInfo;
+    use unit::{Unit, UnitExp};
+    use value::Value;
+
+    const UNIT: UnitExp = UnitExp {
+        unit: Unit::VDC,
+        exponent: 0,
+    };
+
+    #[test]
+    fn test_verify() {
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let info = RecordInfo {
+            name: "test".into(),
+            unit: UNIT,
+            interval: Duration::from_secs(2),
+            duration: Duration::from_secs(60),
+            sample_count: 5,
+            max: Value::new(3.0, 2, UNIT.unit, UNIT.exponent),
+            average: Value::new(2.0, 2, UNIT.unit, UNIT.exponent),
+            min: Value::new(1.0, 2, UNIT.unit, UNIT.exponent),
+            start,
+        };
+        let item = |secs, v| RecordDataItem {
+            value: Value::new(v, 2, UNIT.unit, UNIT.exponent),
+            timestamp: start + chrono::Duration::seconds(secs),
+        };
+
+        let items = vec![
+            item(0, 1.0),
+            item(2, 2.0),
+            item(4, 3.0),
+            item(6, 2.5),
+            item(8, 1.5),
+        ];
+        assert!(info.verify(&items).is_ok());
+
+        let mut wrong = info.clone();
+        wrong.average = Value::new(2.5, 2, UNIT.unit, UNIT.exponent);
+        let issues = wrong.verify(&items).issues;
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            RecordIssue::StatisticMismatch {
+                name: RecordStatistic::Average,
+                ..
+            }
+        ));
+
+        let items = vec![item(0, 1.0), item(2, 2.0), item(2, 2.0), item(8, 3.0)];
+        let issues = info.verify(&items).issues;
+        assert_eq!(issues.len(), 3);
+        assert!(matches!(
+            issues[0],
+            RecordIssue::CountMismatch {
+                expected: 5,
+                actual: 4
+            }
+        ));
+        assert!(matches!(issues[1], RecordIssue::Duplicate { index: 2 }));
+        assert!(matches!(
+            issues[2],
+            RecordIssue::Gap {
+                index: 3,
+                missing: 2
+            }
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        use super::RecordReport;
+
+        let report = RecordReport {
+            issues: vec![RecordIssue::StatisticMismatch {
+                name: RecordStatistic::Max,
+                reported: Value::new(3.0, 2, UNIT.unit, UNIT.exponent),
+                computed: 2.5,
+            }],
+        };
+        let json = ::serde_json::to_value(&report).unwrap();
+        assert_eq!(json["issues"][0]["kind"], "StatisticMismatch");
+        assert_eq!(json["issues"][0]["name"], "Max");
+        let report: RecordReport = ::serde_json::from_value(json).unwrap();
+        assert!(matches!(
+            report.issues[0],
+            RecordIssue::StatisticMismatch {
+                name: RecordStatistic::Max,
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,308 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use measurement::Measurement;
+use mode::Mode;
+use value::Value;
+
+/// Width of text rendering in characters.
+const TEXT_WIDTH: usize = 32;
+
+/// A number of bargraph segments.
+const BARGRAPH_LENGTH: usize = 30;
+
+/// SVG rendering width in pixels.
+const SVG_WIDTH: usize = 320;
+
+/// Secondary field of DMM screen.
+#[derive(Clone, Debug)]
+pub struct ScreenField {
+    /// Field label ("MAX", "REF", etc).
+    pub label: &'static str,
+
+    /// Field value.
+    pub value: Value,
+
+    /// Time since min/max mode start.
+    pub time: Option<Duration>,
+}
+
+/// DMM screen contents built from a measurement.
+#[derive(Clone, Debug)]
+pub struct Screen {
+    /// Mode and submode.
+    pub mode: Mode,
+
+    /// Active indicators ("HOLD", "AUTO", "REL", "MIN MAX", etc).
+    pub indicators: Vec<&'static str>,
+
+    /// Main reading.
+    pub main: Value,
+
+    /// Secondary fields below main reading.
+    pub fields: Vec<ScreenField>,
+
+    /// Bargraph value relative to full scale of current range (-1.0..1.0).
+    pub bargraph: Option<f64>,
+}
+
+fn field(label: &'static str, value: &Value) -> ScreenField {
+    ScreenField {
+        label,
+        value: value.clone(),
+        time: None,
+    }
+}
+
+fn timed_field(label: &'static str, value: &Value, time: Duration) -> ScreenField {
+    ScreenField {
+        label,
+        value: value.clone(),
+        time: Some(time),
+    }
+}
+
+/// Formats duration as "HH:MM:SS".
+fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Escapes XML special characters.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl Screen {
+    /// Builds screen contents from `measurement`.
+    pub fn new(measurement: &Measurement) -> Screen {
+        let mut indicators = Vec::new();
+        if measurement.is_holded() {
+            indicators.push("HOLD");
+        }
+        indicators.push(if measurement.is_auto_range() {
+            "AUTO"
+        } else {
+            "MANUAL"
+        });
+
+        let mode = measurement.mode();
+        let full_scale = measurement.range().full_scale(mode);
+        let bargraph = |fast: &Option<Value>| match (fast, &full_scale) {
+            (Some(fast), Some(full_scale)) if fast.unit.unit == full_scale.unit.unit => {
+                Some((fast.si() / full_scale.si()).clamp(-1.0, 1.0))
+            }
+            _ => None,
+        };
+
+        let (main, fields, bargraph) = match *measurement {
+            Measurement::Normal(ref m) => {
+                let mut fields = Vec::new();
+                if let Some(ref aux1) = m.aux1 {
+                    fields.push(field("AUX1", aux1));
+                }
+                if let Some(ref aux2) = m.aux2 {
+                    fields.push(field("AUX2", aux2));
+                }
+                (m.main.clone(), fields, bargraph(&m.fast))
+            }
+            Measurement::Relative(ref m) => {
+                indicators.push("REL");
+                let fields = vec![field("REF", &m.reference), field("ABS", &m.measurement)];
+                (m.relative.clone(), fields, bargraph(&m.fast))
+            }
+            Measurement::MinMax(ref m) => {
+                indicators.push("MIN MAX");
+                let fields = vec![
+                    timed_field("MAX", &m.max, m.max_time),
+                    timed_field("AVG", &m.average, m.average_time),
+                    timed_field("MIN", &m.min, m.min_time),
+                ];
+                (m.main.clone(), fields, None)
+            }
+            Measurement::Peak(ref m) => {
+                indicators.push("PEAK");
+                (m.max.clone(), vec![field("P-MIN", &m.min)], None)
+            }
+        };
+        if measurement.is_recording() {
+            indicators.push("REC");
+        }
+
+        Screen {
+            mode,
+            indicators,
+            main,
+            fields,
+            bargraph,
+        }
+    }
+
+    /// Renders the screen as multi-line text.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let mode = self.mode.to_string();
+        let indicators = self.indicators.join(" ");
+        let _ = writeln!(
+            text,
+            "{}{:>width$}",
+            mode,
+            indicators,
+            width = TEXT_WIDTH.saturating_sub(mode.chars().count())
+        );
+        let _ = writeln!(text, "{:>width$}", self.main.to_string(), width = TEXT_WIDTH);
+        if let Some(bargraph) = self.bargraph {
+            let _ = writeln!(text, "{}", render_bargraph(bargraph));
+        }
+        for field in &self.fields {
+            let value = match field.time {
+                Some(time) => format!("{} {}", field.value, format_time(time)),
+                None => field.value.to_string(),
+            };
+            let _ = writeln!(
+                text,
+                "{:<6}{:>width$}",
+                field.label,
+                value,
+                width = TEXT_WIDTH - 6
+            );
+        }
+        text
+    }
+
+    /// Renders the screen as SVG image.
+    pub fn to_svg(&self) -> String {
+        let height = 100 + 24 * self.fields.len() + if self.bargraph.is_some() { 24 } else { 0 };
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = SVG_WIDTH,
+            h = height
+        );
+        let _ = writeln!(
+            svg,
+            r##"<rect width="100%" height="100%" fill="#c8d4c0"/>"##
+        );
+        let _ = writeln!(
+            svg,
+            r#"<g font-family="monospace" fill="black"><text x="8" y="20" font-size="14">{}</text><text x="{}" y="20" font-size="14" text-anchor="end">{}</text>"#,
+            escape(&self.mode.to_string()),
+            SVG_WIDTH - 8,
+            escape(&self.indicators.join(" "))
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="72" font-size="40" text-anchor="end">{}</text>"#,
+            SVG_WIDTH - 8,
+            escape(&self.main.to_string())
+        );
+        let mut y = 100;
+        if let Some(bargraph) = self.bargraph {
+            let segment = (SVG_WIDTH - 16) / BARGRAPH_LENGTH;
+            let filled = (bargraph.abs() * BARGRAPH_LENGTH as f64).round() as usize;
+            for i in 0..BARGRAPH_LENGTH {
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{}" height="12" fill-opacity="{}"/>"#,
+                    8 + i * segment,
+                    y - 12,
+                    segment - 2,
+                    if i < filled { "1" } else { "0.15" }
+                );
+            }
+            y += 24;
+        }
+        for field in &self.fields {
+            let time = field.time.map(format_time).unwrap_or_default();
+            let _ = writeln!(
+                svg,
+                r#"<text x="8" y="{y}" font-size="16">{}</text><text x="{}" y="{y}" font-size="16" text-anchor="end">{} {}</text>"#,
+                field.label,
+                SVG_WIDTH - 8,
+                escape(&field.value.to_string()),
+                time,
+                y = y
+            );
+            y += 24;
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
+/// Renders bargraph as text ("-[######.....]" for negative values).
+fn render_bargraph(bargraph: f64) -> String {
+    let filled = (bargraph.abs() * BARGRAPH_LENGTH as f64).round() as usize;
+    format!(
+        "{}[{}{}]",
+        if bargraph < 0.0 { '-' } else { ' ' },
+        "#".repeat(filled),
+        ".".repeat(BARGRAPH_LENGTH - filled)
+    )
+}
+
+impl Measurement {
+    /// Renders the measurement as DMM screen text (see `Screen::to_text`).
+    pub fn to_screen_text(&self) -> String {
+        Screen::new(self).to_text()
+    }
+
+    /// Renders the measurement as DMM screen SVG image (see `Screen::to_svg`).
+    pub fn to_screen_svg(&self) -> String {
+        Screen::new(self).to_svg()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Screen;
+    use measurement::{Measurement, NormalMeasurement, PeakMeasurement};
+    use mode::Mode;
+    use range::Range;
+    use unit::Unit;
+    use value::Value;
+
+    #[test]
+    fn test_normal() {
+        let main = Value::new(-3.0, 4, Unit::VDC, 0);
+        let mut m = NormalMeasurement::new(Mode::VDC_Normal, main, None, None);
+        m.is_holded = true;
+        m.is_auto_range = false;
+        m.range = Range::Step1;
+        m.fast = Some(Value::new(-3.0, 4, Unit::VDC, 0));
+        let m = Measurement::Normal(m);
+        let screen = Screen::new(&m);
+        assert_eq!(screen.indicators, vec!["HOLD", "MANUAL"]);
+        assert_eq!(screen.bargraph, Some(-0.5));
+        let text = screen.to_text();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("VDC") && lines[0].ends_with("HOLD MANUAL"));
+        assert!(lines[1].ends_with("-3.0000 VDC"));
+        assert_eq!(lines[2], format!("-[{}{}]", "#".repeat(15), ".".repeat(15)));
+        assert!(screen.to_svg().starts_with("<svg"));
+    }
+
+    #[test]
+    fn test_overload() {
+        let mut max = Value::new(0.0, 4, Unit::VDC, 0);
+        max.overload_pos = true;
+        let m = Measurement::Peak(PeakMeasurement {
+            mode: Mode::VAC_Peak,
+            is_holded: false,
+            is_auto_range: true,
+            is_recording: true,
+            range: Range::Step1,
+            min: Value::new(-1.0, 4, Unit::VDC, 0),
+            max,
+        });
+        let text = m.to_screen_text();
+        let lines: Vec<_> = text.lines().collect();
+        assert!(lines[0].ends_with("AUTO PEAK REC"));
+        assert!(lines[1].ends_with("OL"));
+        assert!(lines[2].starts_with("P-MIN") && lines[2].ends_with("-1.0000 VDC"));
+    }
+}
@@ -0,0 +1,25 @@
+use std::time::Duration;
+
+/// Conditions to consider DMM settled after mode or range switching.
+#[derive(Clone, Copy, Debug)]
+pub struct Settling {
+    /// Minimal time to wait after the command is acknowledged.
+    pub time: Duration,
+
+    /// A number of consecutive measurements confirming the requested state.
+    ///
+    /// Other state than requested is reported as an error after it persists
+    /// for the same number of measurements (at least 2, a single one can be transitional).
+    /// Wait timeout (5 seconds plus `time`) is extended by 1 second per measurement.
+    pub stable_count: usize,
+}
+
+impl Default for Settling {
+    /// No settling time and one confirming measurement.
+    fn default() -> Settling {
+        Settling {
+            time: Duration::from_millis(0),
+            stable_count: 1,
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::time::{Duration, Instant};
+
+use measurement::Measurement;
+use mode::Mode;
+use range::Range;
+use unit::UnitExp;
+
+/// Period after an acknowledged change when DMM can still send measurements made before it.
+const STALE_PERIOD: u64 = 1000; // 1 second
+
+/// State change acknowledged by DMM but not confirmed by measurements yet.
+#[derive(Clone, Copy, Debug)]
+enum Pending {
+    Mode(Mode),
+    Range(Range),
+    MonitorOff,
+}
+
+impl Pending {
+    fn is_confirmed_by(self, measurement: &Measurement) -> bool {
+        match self {
+            Pending::Mode(mode) => measurement.mode() == mode,
+            Pending::Range(Range::Auto) => measurement.is_auto_range(),
+            Pending::Range(range) => {
+                !measurement.is_auto_range() && measurement.range() == range
+            }
+            Pending::MonitorOff => false,
+        }
+    }
+}
+
+/// Last known DMM state.
+///
+/// It's updated from acknowledged commands and incoming measurements.
+/// `None` means the state is unknown yet.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceState {
+    /// Mode and submode.
+    pub mode: Option<Mode>,
+
+    /// Measuring range.
+    pub range: Option<Range>,
+
+    /// Is auto range selection on?
+    pub is_auto_range: Option<bool>,
+
+    /// Is HOLD on?
+    pub is_holded: Option<bool>,
+
+    /// Is min/max mode on?
+    pub is_min_max: Option<bool>,
+
+    /// Reference value of relative mode (in unit of the mode without metric prefix).
+    pub reference: Option<f32>,
+
+    /// Is monitoring mode on?
+    pub is_monitoring: Option<bool>,
+
+    /// Is recording in progress?
+    pub is_recording: Option<bool>,
+
+    /// Acknowledged change and time until measurements contradicting it are stale.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pending: Option<(Pending, Instant)>,
+}
+
+impl DeviceState {
+    /// Updates the state from `measurement` read from UART at `received`.
+    ///
+    /// Measurements contradicting a change acknowledged less than `STALE_PERIOD` ago
+    /// were made before the change, so they are ignored.
+    pub(crate) fn update(&mut self, measurement: &Measurement, received: Instant) {
+        if let Some((pending, until)) = self.pending {
+            if received < until && !pending.is_confirmed_by(measurement) {
+                return;
+            }
+            self.pending = None;
+        }
+        if let Measurement::Relative(ref m) = *measurement {
+            let unit = UnitExp {
+                unit: m.reference.unit.unit,
+                exponent: 0,
+            };
+            self.reference = m.reference.convert(unit).ok().map(|v| v.value);
+        }
+        self.mode = Some(measurement.mode());
+        self.range = Some(measurement.range());
+        self.is_auto_range = Some(measurement.is_auto_range());
+        self.is_holded = Some(measurement.is_holded());
+        self.is_recording = Some(measurement.is_recording());
+        self.is_min_max = Some(matches!(*measurement, Measurement::MinMax(_)));
+        self.is_monitoring = Some(true);
+    }
+
+    /// Records mode switching acknowledged at `now`.
+    pub(crate) fn set_mode(&mut self, mode: Mode, now: Instant) {
+        self.pending = Some((Pending::Mode(mode), now + Duration::from_millis(STALE_PERIOD)));
+        if self.mode != Some(mode) {
+            // DMM resets range selection and relative reference on mode switching.
+            self.range = None;
+            self.is_auto_range = None;
+            self.reference = None;
+        }
+        self.mode = Some(mode);
+    }
+
+    /// Records range selection acknowledged at `now`.
+    pub(crate) fn set_range(&mut self, range: Range, now: Instant) {
+        self.pending = Some((Pending::Range(range), now + Duration::from_millis(STALE_PERIOD)));
+        self.is_auto_range = Some(range == Range::Auto);
+        self.range = if range == Range::Auto {
+            None
+        } else {
+            Some(range)
+        };
+    }
+
+    pub(crate) fn toggle_hold(&mut self) {
+        self.is_holded = self.is_holded.map(|holded| !holded);
+    }
+
+    /// Records monitoring mode switching acknowledged at `now`.
+    pub(crate) fn set_monitoring(&mut self, on: bool, now: Instant) {
+        self.pending = if on {
+            None
+        } else {
+            Some((Pending::MonitorOff, now + Duration::from_millis(STALE_PERIOD)))
+        };
+        self.is_monitoring = Some(on);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::DeviceState;
+    use measurement::{Measurement, NormalMeasurement};
+    use mode::Mode;
+    use range::Range;
+    use unit::Unit;
+    use value::Value;
+
+    fn measurement(mode: Mode, range: Range) -> Measurement {
+        let mut m = NormalMeasurement::new(mode, Value::new(1.0, 3, Unit::VDC, 0), None, None);
+        m.is_auto_range = range == Range::Auto;
+        m.range = if range == Range::Auto {
+            Range::Step1
+        } else {
+            range
+        };
+        Measurement::Normal(m)
+    }
+
+    #[test]
+    fn test_update() {
+        let t0 = Instant::now();
+        let mut state = DeviceState::default();
+        state.update(&measurement(Mode::VDC_Normal, Range::Step2), t0);
+        assert_eq!(state.mode, Some(Mode::VDC_Normal));
+        assert_eq!(state.range, Some(Range::Step2));
+        assert_eq!(state.is_auto_range, Some(false));
+        assert_eq!(state.is_holded, Some(false));
+        assert_eq!(state.is_min_max, Some(false));
+        assert_eq!(state.is_monitoring, Some(true));
+    }
+
+    #[test]
+    fn test_set_mode() {
+        let t0 = Instant::now();
+        let mut state = DeviceState::default();
+        state.update(&measurement(Mode::VDC_Normal, Range::Step2), t0);
+        state.set_mode(Mode::VAC_Normal, t0);
+        assert_eq!(state.mode, Some(Mode::VAC_Normal));
+        assert_eq!(state.range, None);
+        assert_eq!(state.is_auto_range, None);
+
+        // measurement made before switching
+        let at = |ms| t0 + Duration::from_millis(ms);
+        state.update(&measurement(Mode::VDC_Normal, Range::Step2), at(100));
+        assert_eq!(state.mode, Some(Mode::VAC_Normal));
+        state.update(&measurement(Mode::VAC_Normal, Range::Auto), at(200));
+        assert_eq!(state.is_auto_range, Some(true));
+        // DMM has left the requested mode after confirming it
+        state.update(&measurement(Mode::VDC_Normal, Range::Auto), at(300));
+        assert_eq!(state.mode, Some(Mode::VDC_Normal));
+
+        // not confirmed change expires
+        state.set_mode(Mode::VAC_Normal, t0);
+        state.update(&measurement(Mode::VDC_Normal, Range::Auto), at(1000));
+        assert_eq!(state.mode, Some(Mode::VDC_Normal));
+    }
+
+    #[test]
+    fn test_set_range() {
+        let t0 = Instant::now();
+        let mut state = DeviceState::default();
+        state.update(&measurement(Mode::VDC_Normal, Range::Auto), t0);
+        state.set_range(Range::Step3, t0);
+        assert_eq!(state.range, Some(Range::Step3));
+        assert_eq!(state.is_auto_range, Some(false));
+        state.update(&measurement(Mode::VDC_Normal, Range::Auto), t0);
+        assert_eq!(state.range, Some(Range::Step3));
+        state.update(&measurement(Mode::VDC_Normal, Range::Step3), t0);
+        state.set_range(Range::Auto, t0);
+        assert_eq!(state.range, None);
+        assert_eq!(state.is_auto_range, Some(true));
+    }
+
+    #[test]
+    fn test_set_monitoring() {
+        let t0 = Instant::now();
+        let mut state = DeviceState::default();
+        state.set_monitoring(false, t0);
+        state.update(&measurement(Mode::VDC_Normal, Range::Auto), t0);
+        assert_eq!(state.is_monitoring, Some(false));
+        state.set_monitoring(true, t0);
+        assert_eq!(state.is_monitoring, Some(true));
+    }
+}
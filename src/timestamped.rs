@@ -0,0 +1,39 @@
+use std::time::Instant;
+
+use chrono::NaiveDateTime;
+
+use measurement::Measurement;
+use message::Message;
+
+/// Measurement with host-side reception time.
+#[derive(Clone, Debug)]
+pub struct TimestampedMeasurement {
+    pub measurement: Measurement,
+
+    /// Host wall-clock time when the measurement has been read from UART.
+    pub received: NaiveDateTime,
+
+    /// Monotonic time when the measurement has been read from UART.
+    pub instant: Instant,
+
+    /// Sequence number of the measurement received from DMM (starting with 0).
+    ///
+    /// It's incremented for every measurement including ones consumed while waiting
+    /// replies to commands, so a gap means some measurements were skipped.
+    pub sequence: u64,
+}
+
+/// Message read from DMM, measurements are stamped when they are parsed.
+pub(crate) enum Received {
+    Measurement(TimestampedMeasurement),
+    Other(Message),
+}
+
+impl Received {
+    pub(crate) fn into_message(self) -> Message {
+        match self {
+            Received::Measurement(m) => Message::Measurement(m.measurement),
+            Received::Other(msg) => msg,
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use std::fmt;
+use std::str::FromStr;
 
 use error::*;
-use utils::read_stringz;
+use utils::{normalize_name, read_stringz};
 
 /// Measurement unit.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Unit {
     /// Volts, direct current
     VDC,
@@ -56,7 +58,8 @@ pub enum Unit {
     dBV,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UnitExp {
     pub unit: Unit,
     pub exponent: isize,
@@ -195,37 +198,266 @@ impl UnitExp {
     }
 }
 
+/// Unit rendering style.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UnitStyle {
+    /// Unicode symbols with AC/DC suffix: "mVDC", "°C", "°F", "µF", "kΩ".
+    Unicode,
+
+    /// Unambiguous ASCII names with AC/DC suffix: "mVDC", "degC", "degF", "uF", "kOhm".
+    Ascii,
+
+    /// SI symbols without AC/DC suffix: "mV", "°C", "°F", "µF", "kΩ".
+    Si,
+}
+
+impl Unit {
+    /// Returns unit symbol in `style`.
+    pub fn symbol(self, style: UnitStyle) -> &'static str {
+        match (self, style) {
+            (Unit::VDC, UnitStyle::Si) | (Unit::VAC, UnitStyle::Si) | (Unit::VAcDc, UnitStyle::Si) => "V",
+            (Unit::ADC, UnitStyle::Si) | (Unit::AAC, UnitStyle::Si) | (Unit::AAcDc, UnitStyle::Si) => "A",
+            (Unit::VDC, _) => "VDC",
+            (Unit::VAC, _) => "VAC",
+            (Unit::VAcDc, _) => "Vac+dc",
+            (Unit::ADC, _) => "ADC",
+            (Unit::AAC, _) => "AAC",
+            (Unit::AAcDc, _) => "Aac+dc",
+            (Unit::Celsius, UnitStyle::Ascii) => "degC",
+            (Unit::Celsius, _) => "\u{B0}C",
+            (Unit::Fahrenheit, UnitStyle::Ascii) => "degF",
+            (Unit::Fahrenheit, _) => "\u{B0}F",
+            (Unit::F, _) => "F",
+            (Unit::Hz, _) => "Hz",
+            (Unit::s, _) => "s",
+            (Unit::Percent, _) => "%",
+            (Unit::S, _) => "S",
+            (Unit::Ohm, UnitStyle::Ascii) => "Ohm",
+            (Unit::Ohm, _) => "\u{3A9}",
+            (Unit::dBm, _) => "dBm",
+            (Unit::dBV, _) => "dBV",
+        }
+    }
+}
+
+/// Returns metric prefix of `exponent` in `style`.
+fn prefix(exponent: isize, style: UnitStyle) -> Option<&'static str> {
+    match exponent {
+        -15 => Some("f"),
+        -12 => Some("p"),
+        -9 => Some("n"),
+        -6 if style == UnitStyle::Ascii => Some("u"),
+        -6 => Some("\u{B5}"),
+        -3 => Some("m"),
+        0 => Some(""),
+        3 => Some("k"),
+        6 => Some("M"),
+        9 => Some("G"),
+        12 => Some("T"),
+        _ => None,
+    }
+}
+
+/// Displays `UnitExp` in specified style.
+///
+/// Exponents without metric prefix are displayed as power of 10 ("x10^-4 V", "×10⁻⁴ V").
+pub struct UnitExpDisplay {
+    unit: UnitExp,
+    style: UnitStyle,
+}
+
+impl fmt::Display for UnitExpDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = self.unit.unit.symbol(self.style);
+        if let Some(prefix) = prefix(self.unit.exponent, self.style) {
+            return write!(f, "{}{}", prefix, symbol);
+        }
+        if self.style == UnitStyle::Ascii {
+            return write!(f, "x10^{} {}", self.unit.exponent, symbol);
+        }
+        f.write_str("\u{D7}10")?;
+        for c in self.unit.exponent.to_string().chars() {
+            f.write_str(match c {
+                '-' => "\u{207B}",
+                '0' => "\u{2070}",
+                '1' => "\u{B9}",
+                '2' => "\u{B2}",
+                '3' => "\u{B3}",
+                '4' => "\u{2074}",
+                '5' => "\u{2075}",
+                '6' => "\u{2076}",
+                '7' => "\u{2077}",
+                '8' => "\u{2078}",
+                _ => "\u{2079}",
+            })?;
+        }
+        write!(f, " {}", symbol)
+    }
+}
+
+impl UnitExp {
+    /// Returns displayable unit in `style`.
+    pub fn display(self, style: UnitStyle) -> UnitExpDisplay {
+        UnitExpDisplay { unit: self, style }
+    }
+}
+
+/// Unicode style (`UnitStyle::Unicode`), alternate form (`{:#}`) is ASCII style.
 impl fmt::Display for UnitExp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let prefix = match self.exponent {
-            -12 => "p",
-            -9 => "n",
-            -6 => "u",
-            -3 => "m",
-            0 => "",
-            3 => "k",
-            6 => "M",
-            9 => "G",
-            _ => return Err(fmt::Error),
+        let style = if f.alternate() {
+            UnitStyle::Ascii
+        } else {
+            UnitStyle::Unicode
         };
-        let unit = match self.unit {
-            Unit::VDC => "VDC",
-            Unit::VAC => "VAC",
-            Unit::VAcDc => "Vac+dc",
-            Unit::ADC => "ADC",
-            Unit::AAC => "AAC",
-            Unit::AAcDc => "Aac+dc",
-            Unit::Celsius => "C",
-            Unit::Fahrenheit | Unit::F => "F",
-            Unit::Hz => "Hz",
-            Unit::s => "s",
-            Unit::Percent => "%",
-            Unit::S => "S",
-            Unit::Ohm => "Ohm",
-            Unit::dBm => "dBm",
-            Unit::dBV => "dBV",
+        self.display(style).fmt(f)
+    }
+}
+
+/// Unicode style (`UnitStyle::Unicode`), alternate form (`{:#}`) is ASCII style.
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = if f.alternate() {
+            UnitStyle::Ascii
+        } else {
+            UnitStyle::Unicode
+        };
+        f.write_str(self.symbol(style))
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    /// Parses unit from its symbol or name.
+    ///
+    /// Symbols are case-sensitive ("s" is second, "S" is siemens,
+    /// "F" is farad, "°F" is Fahrenheit degree, "C" is Celsius degree),
+    /// names are case-insensitive ("vdc", "ohm", "celsius", "farad", etc).
+    fn from_str(s: &str) -> Result<Unit> {
+        let unit = match s {
+            "V" => Unit::VDC,
+            "A" => Unit::ADC,
+            "C" | "\u{B0}C" | "\u{2103}" => Unit::Celsius,
+            "\u{B0}F" | "\u{2109}" => Unit::Fahrenheit,
+            "F" => Unit::F,
+            "s" => Unit::s,
+            "S" => Unit::S,
+            "%" => Unit::Percent,
+            "\u{2126}" | "\u{3A9}" | "~" => Unit::Ohm,
+            _ => match normalize_name(s).as_ref() {
+                "vdc" => Unit::VDC,
+                "vac" => Unit::VAC,
+                "vac+dc" | "vacdc" => Unit::VAcDc,
+                "adc" => Unit::ADC,
+                "aac" => Unit::AAC,
+                "aac+dc" | "aacdc" => Unit::AAcDc,
+                "celsius" | "degc" => Unit::Celsius,
+                "fahrenheit" | "degf" => Unit::Fahrenheit,
+                "farad" | "farads" => Unit::F,
+                "hz" | "hertz" => Unit::Hz,
+                "second" | "seconds" | "sec" => Unit::s,
+                "percent" | "percents" => Unit::Percent,
+                "siemens" => Unit::S,
+                "ohm" | "ohms" => Unit::Ohm,
+                "dbm" => Unit::dBm,
+                "dbv" => Unit::dBV,
+                _ => return Err(ErrorKind::UnknownUnitName(s.into()).into()),
+            },
+        };
+        Ok(unit)
+    }
+}
+
+impl FromStr for UnitExp {
+    type Err = Error;
+
+    /// Parses unit with optional metric prefix ("mVDC", "kOhm", "uF", "µA", "MHz").
+    ///
+    /// See `Unit::from_str` for unit names.
+    fn from_str(s: &str) -> Result<UnitExp> {
+        if let Ok(unit) = s.parse::<Unit>() {
+            return Ok(UnitExp { unit, exponent: 0 });
+        }
+        let mut chars = s.chars();
+        let exponent = match chars.next() {
+            Some('f') => -15,
+            Some('p') => -12,
+            Some('n') => -9,
+            Some('u') | Some('\u{B5}') | Some('\u{3BC}') => -6,
+            Some('m') => -3,
+            Some('k') => 3,
+            Some('M') => 6,
+            Some('G') => 9,
+            Some('T') => 12,
+            _ => return Err(ErrorKind::UnknownUnitName(s.into()).into()),
         };
+        match chars.as_str().parse::<Unit>() {
+            Ok(unit) => Ok(UnitExp { unit, exponent }),
+            Err(_) => Err(ErrorKind::UnknownUnitName(s.into()).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Unit, UnitExp, UnitStyle};
+
+    #[test]
+    fn test_unit_from_str() {
+        for &unit in &[
+            Unit::VDC,
+            Unit::VAC,
+            Unit::VAcDc,
+            Unit::ADC,
+            Unit::AAC,
+            Unit::AAcDc,
+            Unit::Celsius,
+            Unit::Fahrenheit,
+            Unit::F,
+            Unit::Hz,
+            Unit::s,
+            Unit::Percent,
+            Unit::S,
+            Unit::Ohm,
+            Unit::dBm,
+            Unit::dBV,
+        ] {
+            assert_eq!(unit.to_string().parse::<Unit>().unwrap(), unit);
+            assert_eq!(format!("{:#}", unit).parse::<Unit>().unwrap(), unit);
+        }
+        assert_eq!("vdc".parse::<Unit>().unwrap(), Unit::VDC);
+        assert_eq!("OHM".parse::<Unit>().unwrap(), Unit::Ohm);
+        assert!("X".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn test_unit_exp_display() {
+        let unit = |unit, exponent| UnitExp { unit, exponent };
+        assert_eq!(unit(Unit::Celsius, 0).to_string(), "\u{B0}C");
+        assert_eq!(format!("{:#}", unit(Unit::Fahrenheit, 0)), "degF");
+        assert_eq!(unit(Unit::F, -6).to_string(), "\u{B5}F");
+        assert_eq!(format!("{:#}", unit(Unit::Ohm, 3)), "kOhm");
+        assert_eq!(unit(Unit::VAcDc, -3).display(UnitStyle::Si).to_string(), "mV");
+        assert_eq!(unit(Unit::VDC, -4).to_string(), "\u{D7}10\u{207B}\u{2074} VDC");
+        assert_eq!(format!("{:#}", unit(Unit::VDC, -4)), "x10^-4 VDC");
+        for &style in &[UnitStyle::Unicode, UnitStyle::Ascii, UnitStyle::Si] {
+            for &exponent in &[-9, -6, -3, 0, 3, 6] {
+                let u = unit(Unit::Ohm, exponent);
+                assert_eq!(u.display(style).to_string().parse::<UnitExp>().unwrap(), u);
+            }
+        }
+    }
 
-        write!(f, "{}{}", prefix, unit)
+    #[test]
+    fn test_unit_exp_from_str() {
+        let parse = |s: &str| s.parse::<UnitExp>().unwrap();
+        assert_eq!(parse("mVDC"), UnitExp { unit: Unit::VDC, exponent: -3 });
+        assert_eq!(parse("kOhm"), UnitExp { unit: Unit::Ohm, exponent: 3 });
+        assert_eq!(parse("\u{B5}F"), UnitExp { unit: Unit::F, exponent: -6 });
+        assert_eq!(parse("ms"), UnitExp { unit: Unit::s, exponent: -3 });
+        assert_eq!(parse("MHz"), UnitExp { unit: Unit::Hz, exponent: 6 });
+        assert_eq!(parse("dBm"), UnitExp { unit: Unit::dBm, exponent: 0 });
+        assert!("xV".parse::<UnitExp>().is_err());
     }
 }
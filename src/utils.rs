@@ -1,9 +1,14 @@
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Local, NaiveDate, NaiveDateTime};
 
 use error::*;
 
+/// Returns current host time.
+pub(crate) fn now() -> NaiveDateTime {
+    Local::now().naive_local()
+}
+
 pub(crate) fn read_stringz(data: &[u8]) -> String {
-    let zestr: Vec<u8> = data.into_iter().cloned().take_while(|&b| b != 0).collect();
+    let zestr: Vec<u8> = data.iter().cloned().take_while(|&b| b != 0).collect();
     String::from_utf8_lossy(&zestr).into_owned()
 }
 
@@ -29,3 +34,33 @@ pub(crate) fn read_datetime(data: &[u8]) -> Result<NaiveDateTime> {
 pub(crate) fn allowed_char(c: char) -> bool {
     (c as u32) >= 0x20 && (c as u32) <= 0x7E
 }
+
+/// Lowercases `name` and removes separators ('_', '-', spaces).
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|&c| c != '_' && c != '-' && !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// (De)serializes `Duration` as a number of whole seconds.
+#[cfg(feature = "serde")]
+pub(crate) mod duration_secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(d.as_secs())
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Duration::from_secs(u64::deserialize(deserializer)?))
+    }
+}
@@ -1,12 +1,15 @@
+use std::cmp::Ordering;
 use std::fmt;
 
 use byteorder::{ByteOrder, LittleEndian};
 
+use decimal::Decimal;
 use error::*;
-use unit::UnitExp;
+use unit::{Unit, UnitExp, UnitStyle};
 
 /// Measured value.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Value {
     /// Is `value` under negative limit?
     pub overload_neg: bool,
@@ -75,19 +78,249 @@ impl Value {
     }
 }
 
-impl fmt::Display for Value {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+#[cfg(test)]
+impl Value {
+    /// Creates not overloaded value for tests.
+    pub(crate) fn new(value: f32, precision: usize, unit: Unit, exponent: isize) -> Value {
+        Value {
+            overload_neg: false,
+            overload_pos: false,
+            value,
+            precision: Some(precision),
+            unit: UnitExp { unit, exponent },
+        }
+    }
+}
+
+impl Value {
+    /// Returns exact value with `precision` digits after decimal point as displayed by DMM.
+    ///
+    /// Returns `None` if the value has no precision (bargraph values) or it's overloaded.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        if self.overload_neg || self.overload_pos {
+            return None;
+        }
+        self.precision.and_then(|prc| Decimal::from_f32(self.value, prc as u32))
+    }
+
+    /// Returns exact value in SI units without metric prefix (see `to_decimal`).
+    fn si_decimal(&self) -> Option<Decimal> {
+        let decimal = self.to_decimal()?;
+        let exponent = self.unit.exponent;
+        if exponent <= 0 {
+            return Some(Decimal {
+                mantissa: decimal.mantissa,
+                scale: decimal.scale + exponent.unsigned_abs() as u32,
+            });
+        }
+        let exponent = exponent as u32;
+        if decimal.scale >= exponent {
+            return Some(Decimal {
+                mantissa: decimal.mantissa,
+                scale: decimal.scale - exponent,
+            });
+        }
+        10i64
+            .checked_pow(exponent - decimal.scale)
+            .and_then(|factor| decimal.mantissa.checked_mul(factor))
+            .map(|mantissa| Decimal { mantissa, scale: 0 })
+    }
+
+    /// Returns the value in SI units without metric prefix (12.3 mV is 0.0123).
+    ///
+    /// Positive overload is `f64::INFINITY`, negative overload is `f64::NEG_INFINITY`.
+    pub fn si(&self) -> f64 {
+        if self.overload_pos {
+            return f64::INFINITY;
+        }
         if self.overload_neg {
+            return f64::NEG_INFINITY;
+        }
+        self.raw_si()
+    }
+
+    /// Returns the value in SI units ignoring overload flags.
+    fn raw_si(&self) -> f64 {
+        f64::from(self.value) * 10f64.powi(self.unit.exponent as i32)
+    }
+
+    /// Converts the value to `unit`.
+    ///
+    /// Metric prefix can be changed for any unit (mV to V, kOhm to MOhm, etc),
+    /// Celsius degrees can be converted to Fahrenheit degrees and vice versa
+    /// (absolute temperatures only, not T1-T2 differences).
+    /// Precision is adjusted to keep the same resolution.
+    pub fn convert(&self, unit: UnitExp) -> Result<Value> {
+        let si = self.raw_si();
+        let si = match (self.unit.unit, unit.unit) {
+            (from, to) if from == to => si,
+            (Unit::Celsius, Unit::Fahrenheit) => si * 9.0 / 5.0 + 32.0,
+            (Unit::Fahrenheit, Unit::Celsius) => (si - 32.0) * 5.0 / 9.0,
+            _ => return Err(ErrorKind::IncompatibleUnits(self.unit, unit).into()),
+        };
+        let shift = unit.exponent - self.unit.exponent;
+        Ok(Value {
+            overload_neg: self.overload_neg,
+            overload_pos: self.overload_pos,
+            value: (si / 10f64.powi(unit.exponent as i32)) as f32,
+            precision: self.precision.map(|prc| (prc as isize + shift).max(0) as usize),
+            unit,
+        })
+    }
+
+    /// Compares values in different metric prefixes or temperature scales.
+    ///
+    /// Values of the same unit with known precision are compared exactly
+    /// as displayed by DMM (see `to_decimal`).
+    /// Other values are equal if they differ by `f32` rounding error only.
+    /// Returns an error if units are incompatible (see `convert`)
+    /// or the values can't be compared (NaN).
+    pub fn compare(&self, other: &Value) -> Result<Ordering> {
+        if self.unit.unit == other.unit.unit {
+            if let (Some(a), Some(b)) = (self.si_decimal(), other.si_decimal()) {
+                return Ok(a.cmp(&b));
+            }
+        }
+        let a = self.si();
+        let b = other.convert(self.unit)?.si();
+        if a.is_finite() && b.is_finite() {
+            let tolerance = a.abs().max(b.abs()) * f64::from(f32::EPSILON) * 4.0;
+            if (a - b).abs() <= tolerance {
+                return Ok(Ordering::Equal);
+            }
+        }
+        a.partial_cmp(&b)
+            .ok_or_else(|| ErrorKind::IncomparableValues.into())
+    }
+}
+
+/// Displays `Value` in specified unit style.
+///
+/// Values with exponents without metric prefix are displayed in engineering notation
+/// (`0.5` with exponent -4 is displayed as "50 µV").
+pub struct ValueDisplay<'a> {
+    value: &'a Value,
+    style: UnitStyle,
+}
+
+impl<'a> fmt::Display for ValueDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.value.overload_neg {
             return write!(f, "-OL");
         }
-        if self.overload_pos {
+        if self.value.overload_pos {
             return write!(f, "OL");
         }
-        if let Some(prc) = self.precision {
-            write!(f, "{:.*}", prc, self.value)?;
+        let exponent = self.value.unit.exponent;
+        let engineering = if exponent % 3 != 0 {
+            let unit = UnitExp {
+                unit: self.value.unit.unit,
+                exponent: exponent - exponent.rem_euclid(3),
+            };
+            self.value.convert(unit).ok()
         } else {
-            write!(f, "{}", self.value)?;
+            None
+        };
+        let value = engineering.as_ref().unwrap_or(self.value);
+        if let Some(decimal) = value.to_decimal() {
+            write!(f, "{}", decimal)?;
+        } else {
+            write!(f, "{}", value.value)?;
         }
-        write!(f, " {}", self.unit)
+        write!(f, " {}", value.unit.display(self.style))
+    }
+}
+
+impl Value {
+    /// Returns displayable value with unit in `style`.
+    pub fn display<'a>(&'a self, style: UnitStyle) -> ValueDisplay<'a> {
+        ValueDisplay { value: self, style }
+    }
+}
+
+/// Unicode style (`UnitStyle::Unicode`), alternate form (`{:#}`) is ASCII style.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = if f.alternate() {
+            UnitStyle::Ascii
+        } else {
+            UnitStyle::Unicode
+        };
+        self.display(style).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::Value;
+    use error::ErrorKind;
+    use unit::{Unit, UnitExp, UnitStyle};
+
+    #[test]
+    fn test_si() {
+        assert!((Value::new(12.3, 1, Unit::VDC, -3).si() - 0.0123).abs() < 1e-9);
+        assert!((Value::new(4.7, 2, Unit::Ohm, 3).si() - 4700.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_convert() {
+        let v = Value::new(12.3, 1, Unit::VDC, -3)
+            .convert(UnitExp {
+                unit: Unit::VDC,
+                exponent: 0,
+            })
+            .unwrap();
+        assert!((v.value - 0.0123).abs() < 1e-7);
+        assert_eq!(v.precision, Some(4));
+
+        let f = Value::new(100.0, 1, Unit::Celsius, 0)
+            .convert(UnitExp {
+                unit: Unit::Fahrenheit,
+                exponent: 0,
+            })
+            .unwrap();
+        assert_eq!(f.value, 212.0);
+
+        assert!(Value::new(1.0, 0, Unit::VDC, 0)
+            .convert(UnitExp {
+                unit: Unit::ADC,
+                exponent: 0,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Value::new(4.7, 2, Unit::Ohm, 3).to_string(), "4.70 k\u{3A9}");
+        assert_eq!(format!("{:#}", Value::new(21.5, 1, Unit::Celsius, 0)), "21.5 degC");
+        assert_eq!(
+            Value::new(1.0003, 4, Unit::VAC, 0).display(UnitStyle::Si).to_string(),
+            "1.0003 V"
+        );
+        assert_eq!(Value::new(0.5, 1, Unit::VDC, -4).to_string(), "50 \u{B5}VDC");
+    }
+
+    #[test]
+    fn test_compare() {
+        let a = Value::new(12.3, 1, Unit::VDC, -3);
+        let b = Value::new(0.0123, 4, Unit::VDC, 0);
+        let c = Value::new(0.013, 3, Unit::VDC, 0);
+        assert_eq!(a.compare(&c).unwrap(), Ordering::Less);
+        assert_eq!(c.compare(&a).unwrap(), Ordering::Greater);
+        assert_eq!(a.compare(&b).unwrap(), Ordering::Equal);
+        // the difference is within f32 rounding error, but displayed digits differ
+        let d = Value::new(6000.0, 3, Unit::Ohm, 3);
+        let e = Value::new(6000.001, 3, Unit::Ohm, 3);
+        assert_eq!(d.compare(&e).unwrap(), Ordering::Less);
+        let mut f = Value::new(6.0, 3, Unit::Ohm, 6);
+        f.precision = None;
+        assert_eq!(d.compare(&f).unwrap(), Ordering::Equal);
+        assert!(a.compare(&Value::new(1.0, 0, Unit::VAC, 0)).is_err());
+        assert!(matches!(
+            *a.compare(&Value::new(f32::NAN, 0, Unit::VDC, 0)).unwrap_err().kind(),
+            ErrorKind::IncomparableValues
+        ));
     }
 }